@@ -0,0 +1,287 @@
+use std::fmt::Write;
+use crate::{
+    pad::escape_len,
+    parse::{basic, Blink, Script, Underline},
+    xterm_rgb,
+    Color,
+    StyleState,
+};
+
+
+/// How the styles of text are written in HTML.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Markup {
+    /// Every style is written in a `style` attribute. This is the default.
+    #[default]
+    Inline,
+    /// Attributes and indexed colors are written as classes, to be defined by
+    ///     a stylesheet. RGB colors are still written in a `style` attribute.
+    ///
+    /// The classes of attributes are `sgr-bold`, `sgr-faint`, `sgr-italic`,
+    ///     `sgr-uline`, `sgr-uline-double`, `sgr-uline-curly`,
+    ///     `sgr-uline-dotted`, `sgr-uline-dashed`, `sgr-blink`, `sgr-invert`,
+    ///     `sgr-conceal`, `sgr-strike`, `sgr-overline`, `sgr-super` and
+    ///     `sgr-sub`. The classes of colors are `sgr-fg-N`, `sgr-bg-N` and
+    ///     `sgr-ul-N`, where `N` is an index from 0 to 255, and the 16 basic
+    ///     colors are 0 to 15.
+    Classes,
+}
+
+
+impl StyleState {
+    /// List the classes and CSS declarations of a `<span>` in this state.
+    fn fmt_span(&self, markup: Markup) -> (Vec<String>, Vec<String>) {
+        let classes = markup == Markup::Classes;
+        let mut class: Vec<String> = Vec::new();
+        let mut style: Vec<String> = Vec::new();
+
+        let mut flag = |on: bool, name: &str, css: &str| match (on, classes) {
+            (false, _) => {}
+            (true, true) => class.push(format!("sgr-{name}")),
+            (true, false) => style.push(String::from(css)),
+        };
+
+        flag(self.bold, "bold", "font-weight:bold");
+        flag(self.faint, "faint", "opacity:0.5");
+        flag(self.italic, "italic", "font-style:italic");
+        flag(self.blink != Blink::None, "blink", "text-decoration:blink");
+        flag(self.conceal, "conceal", "visibility:hidden");
+        flag(self.strike, "strike", "text-decoration:line-through");
+        flag(self.overline, "overline", "text-decoration:overline");
+        flag(self.script == Script::Super, "super", "vertical-align:super");
+        flag(self.script == Script::Sub, "sub", "vertical-align:sub");
+
+        if let Some((name, css)) = underline(self.underline) {
+            if classes {
+                class.push(String::from(name));
+            } else {
+                style.push(format!("text-decoration:{css}"));
+            }
+        }
+
+        //  Any lines may be drawn together.
+        let lines: Vec<&str> = style.iter()
+            .filter_map(|d| d.strip_prefix("text-decoration:"))
+            .collect();
+
+        if lines.len() > 1 {
+            let lines = lines.join(" ");
+            style.retain(|d| !d.starts_with("text-decoration:"));
+            style.push(format!("text-decoration:{lines}"));
+        }
+
+        let (fg, bg) = if !self.invert {
+            (self.fg, self.bg)
+        } else if classes {
+            class.push(String::from("sgr-invert"));
+            (self.fg, self.bg)
+        } else {
+            //  Without a color of its own, an inverted layer takes the default
+            //      color of the other, as named by CSS.
+            let fg = css_color(self.bg).unwrap_or_else(|| "Canvas".into());
+            let bg = css_color(self.fg).unwrap_or_else(|| "CanvasText".into());
+            style.push(format!("color:{fg}"));
+            style.push(format!("background-color:{bg}"));
+            (None, None)
+        };
+
+        let layers = [
+            ("fg", "color", fg),
+            ("bg", "background-color", bg),
+            ("ul", "text-decoration-color", self.uline_color),
+        ];
+
+        for (name, property, color) in layers {
+            match (color, classes) {
+                (None, _) => {}
+                (Some(Color::Rgb(..)), _) | (_, false) => {
+                    let css = css_color(color).unwrap_or_default();
+                    style.push(format!("{property}:{css}"));
+                }
+                (Some(color), true) => {
+                    let index = index(color).unwrap_or_default();
+                    class.push(format!("sgr-{name}-{index}"));
+                }
+            }
+        }
+
+        (class, style)
+    }
+}
+
+
+/// Get the class name and CSS line of an underline.
+const fn underline(style: Underline) -> Option<(&'static str, &'static str)> {
+    match style {
+        Underline::None => None,
+        Underline::Single => Some(("sgr-uline", "underline")),
+        Underline::Double => Some(("sgr-uline-double", "underline double")),
+        Underline::Curly => Some(("sgr-uline-curly", "underline wavy")),
+        Underline::Dotted => Some(("sgr-uline-dotted", "underline dotted")),
+        Underline::Dashed => Some(("sgr-uline-dashed", "underline dashed")),
+    }
+}
+
+
+/// Get the palette index of a color, if it has one.
+fn index(color: Color) -> Option<u8> {
+    match color {
+        Color::Indexed(index) => Some(index),
+        Color::Rgb(..) => None,
+        basic => (0..16).find(|&i| self::basic(i) == basic).map(|i| i as u8),
+    }
+}
+
+
+/// Write a color in CSS, with the values of the xterm 256-color palette.
+fn css_color(color: Option<Color>) -> Option<String> {
+    let (r, g, b) = match color? {
+        Color::Rgb(r, g, b) => (r, g, b),
+        color => xterm_rgb(index(color)?),
+    };
+
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+
+/// Escape text for HTML.
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+
+/// The schemes of the URIs that a hyperlink may be converted with. Text may be
+///     untrusted, so any other URI, such as `javascript:`, could run a script.
+const SCHEMES: [&str; 4] = ["http", "https", "file", "mailto"];
+
+/// Check whether a URI has one of the allowed [`SCHEMES`].
+fn allowed(uri: &str) -> bool {
+    uri.split_once(':').is_some_and(|(scheme, _)| {
+        SCHEMES.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+
+/// Convert text with SGR sequences, such as the output of the macros of
+///     `sgr_macros`, into HTML. Each run of text with the same style is put in
+///     a `<span>`, and each hyperlink becomes an `<a>` element. Any other
+///     control sequence is removed, and the text is escaped.
+///
+/// Only a hyperlink to an `http`, `https`, `file`, or `mailto` URI becomes an
+///     element. The text of any other hyperlink is left as plain text.
+///
+/// The output is meant to be put in an element that preserves whitespace, such
+///     as `<pre>`. Basic and indexed colors use the values of the xterm
+///     256-color palette.
+/// ```
+/// use sgr_runtime::{html, Markup};
+///
+/// assert_eq!(
+///     html(sgr_macros::sgr_bold!(sgr_macros::red!("a<b")), Markup::Inline),
+///     "<span style=\"font-weight:bold;color:#cd0000\">a&lt;b</span>",
+/// );
+/// assert_eq!(
+///     html(sgr_macros::sgr_bold!(sgr_macros::red!("a<b")), Markup::Classes),
+///     "<span class=\"sgr-bold sgr-fg-1\">a&lt;b</span>",
+/// );
+/// ```
+pub fn html(text: &str, markup: Markup) -> String {
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut state = StyleState::default();
+    let mut link: Option<&str> = None;
+
+    //  What is open in the output, which may lag behind the state until some
+    //      text is written.
+    let mut span: Option<StyleState> = None;
+    let mut anchor: Option<&str> = None;
+
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let start = rest.find('\x1B').unwrap_or(rest.len());
+        let (plain, tail) = rest.split_at(start);
+
+        if !plain.is_empty() {
+            let styled = (!state.is_empty()).then_some(state);
+
+            if anchor != link || span != styled {
+                if span.take().is_some() {
+                    out.push_str("</span>");
+                }
+
+                if anchor != link {
+                    if anchor.take().is_some() {
+                        out.push_str("</a>");
+                    }
+
+                    if let Some(url) = link {
+                        out.push_str("<a href=\"");
+                        escape(&mut out, url);
+                        out.push_str("\">");
+                        anchor = link;
+                    }
+                }
+
+                if let Some(styled) = styled {
+                    let (class, style) = styled.fmt_span(markup);
+                    out.push_str("<span");
+
+                    if !class.is_empty() {
+                        let _ = write!(out, " class=\"{}\"", class.join(" "));
+                    }
+
+                    if !style.is_empty() {
+                        let _ = write!(out, " style=\"{}\"", style.join(";"));
+                    }
+
+                    out.push('>');
+                    span = Some(styled);
+                }
+            }
+
+            escape(&mut out, plain);
+        }
+
+        if tail.is_empty() {
+            break;
+        }
+
+        let len = escape_len(tail).unwrap_or(1);
+        let seq = &tail[..len];
+        rest = &tail[len..];
+
+        if let Some(params) = seq.strip_prefix("\x1B[")
+            .and_then(|seq| seq.strip_suffix('m'))
+        {
+            //  Parameters that are not understood are skipped.
+            let _ = state.apply(params);
+        } else if let Some(command) = seq.strip_prefix("\x1B]8;") {
+            //  The URI follows the parameters, and an empty URI ends a link.
+            let command = command.strip_suffix("\x1B\\")
+                .or_else(|| command.strip_suffix('\x07'))
+                .unwrap_or(command);
+            let uri = command.split_once(';').map_or("", |(_, uri)| uri);
+            link = allowed(uri).then_some(uri);
+        }
+    }
+
+    if span.is_some() {
+        out.push_str("</span>");
+    }
+
+    if anchor.is_some() {
+        out.push_str("</a>");
+    }
+
+    out
+}
+
@@ -0,0 +1,317 @@
+//! Runtime support for [`sgr_macros`]. Since that crate can only export
+//!     macros, anything that must happen at runtime is provided here instead.
+//!
+//! ## Styles
+//!
+//! When colors and attributes are only known at runtime, such as from a
+//!     configuration file, a [`Style`] can be built from them instead. Its
+//!     output is the same as that of the equivalent macros.
+//! ```
+//! use sgr_runtime::{Attr, Color, Style};
+//!
+//! let level = 2;
+//! let color = if level > 1 { Color::Red } else { Color::Yellow };
+//! let style = Style::new().attr(Attr::Bold).fg(color);
+//!
+//! assert_eq!(
+//!     format!("{}", style.paint("Error")),
+//!     sgr_macros::sgr!(bold, red; "Error"),
+//! );
+//! ```
+//!
+//! ## Gating
+//!
+//! When a macro from `sgr_macros` is invoked with the `?` sigil, its output
+//!     consults this crate to decide whether control sequences should be
+//!     written at all. A [`Style`] may do the same, with [`Style::gate`].
+//!
+//! Color is enabled or disabled for each output stream, following the common
+//!     conventions of command-line programs, in order of priority:
+//! 1. An override set by [`set_override`], such as from a `--color` option.
+//! 2. If `NO_COLOR` is set to anything other than an empty string, color is
+//!    disabled.
+//! 3. If `CLICOLOR_FORCE` is set to anything other than an empty string or
+//!    `0`, color is enabled.
+//! 4. If `TERM` is `dumb`, color is disabled.
+//! 5. If `CLICOLOR` is `0`, color is disabled.
+//! 6. Otherwise, color is enabled only if the stream is a terminal.
+//!
+//! The environment is read only once for each stream, and then remembered.
+//! ```
+//! use sgr_runtime::Stream;
+//!
+//! sgr_runtime::set_override(Some(false));
+//! assert_eq!(sgr_macros::red!(@? "Text"), "Text");
+//! assert!(!sgr_runtime::enabled(Stream::Stdout));
+//!
+//! sgr_runtime::set_override(Some(true));
+//! assert_eq!(sgr_macros::red!(@? "Text"), "\x1B[31mText\x1B[39m");
+//! ```
+//!
+//! ## Padding
+//!
+//! The standard formatting macros pad text by counting its characters, which
+//!     includes the characters of control sequences. A [`Pad`] counts only the
+//!     columns that text occupies in a terminal instead, as found by
+//!     [`width`]. It is used by `sgr_macros` to pad arguments that are colored
+//!     at runtime.
+//!
+//! ## Parsing
+//!
+//! The output of the macros can also be read back. With [`parse`], text is
+//!     split into runs, each with the [`StyleState`] that a terminal would
+//!     show it in. This may be used to test output, or to process it for a
+//!     medium other than a terminal.
+//! ```
+//! use sgr_runtime::{parse, StyleState};
+//!
+//! let runs = parse(sgr_macros::sgr_italic!("Text")).unwrap();
+//! let italic = StyleState { italic: true, ..StyleState::default() };
+//!
+//! assert_eq!(runs, [(String::from("Text"), italic)]);
+//! ```
+//!
+//! ## HTML
+//!
+//! Text written for a terminal can be shown in a web page instead with
+//!     [`html`], which turns its control sequences into `<span>` elements, and
+//!     its hyperlinks into `<a>` elements. The `sgr_html!` macro does the same
+//!     to a literal at compile-time.
+//! ```
+//! use sgr_runtime::{html, Markup};
+//!
+//! assert_eq!(
+//!     html(sgr_macros::red!("Error"), Markup::Classes),
+//!     "<span class=\"sgr-fg-1\">Error</span>",
+//! );
+//! ```
+//!
+//! [`sgr_macros`]: https://docs.rs/sgr_macros
+
+mod html;
+mod pad;
+mod parse;
+mod style;
+
+pub use html::*;
+pub use pad::*;
+pub use parse::*;
+pub use style::*;
+
+use std::{
+    ffi::OsString,
+    fmt::{self, Debug, Display, Formatter, Write},
+    io::IsTerminal,
+    sync::{atomic::{AtomicU8, Ordering}, OnceLock},
+};
+
+
+const AUTO: u8 = 0;
+const ALWAYS: u8 = 1;
+const NEVER: u8 = 2;
+
+static OVERRIDE: AtomicU8 = AtomicU8::new(AUTO);
+static STDOUT: OnceLock<bool> = OnceLock::new();
+static STDERR: OnceLock<bool> = OnceLock::new();
+
+
+/// An output stream, which may or may not support color.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    fn is_terminal(&self) -> bool {
+        match self {
+            Self::Stdout => std::io::stdout().is_terminal(),
+            Self::Stderr => std::io::stderr().is_terminal(),
+        }
+    }
+}
+
+
+/// Decide whether color should be enabled, given a way to read environment
+///     variables, and whether the stream is a terminal.
+pub fn detect(var: impl Fn(&str) -> Option<OsString>, tty: bool) -> bool {
+    let is = |name: &str, value: &str| var(name).is_some_and(|v| v == value);
+    let set = |name: &str| var(name).is_some_and(|v| !v.is_empty());
+
+    if set("NO_COLOR") {
+        false
+    } else if set("CLICOLOR_FORCE") && !is("CLICOLOR_FORCE", "0") {
+        true
+    } else if is("TERM", "dumb") || is("CLICOLOR", "0") {
+        false
+    } else {
+        tty
+    }
+}
+
+
+/// Check whether color is enabled for a stream.
+pub fn enabled(stream: Stream) -> bool {
+    match OVERRIDE.load(Ordering::Relaxed) {
+        ALWAYS => return true,
+        NEVER => return false,
+        _ => {}
+    }
+
+    let cell = match stream {
+        Stream::Stdout => &STDOUT,
+        Stream::Stderr => &STDERR,
+    };
+
+    *cell.get_or_init(|| {
+        detect(|name| std::env::var_os(name), stream.is_terminal())
+    })
+}
+
+
+/// Force color to be enabled or disabled for all streams, or with `None`,
+///     return to detecting it from the environment.
+pub fn set_override(value: Option<bool>) {
+    let value = match value {
+        None => AUTO,
+        Some(true) => ALWAYS,
+        Some(false) => NEVER,
+    };
+
+    OVERRIDE.store(value, Ordering::Relaxed);
+}
+
+
+/// Select between the two strings of a gated Literal Mode invocation, which
+///     are colored and plain, respectively.
+pub fn select<'a>(pair: (&'a str, &'a str), stream: Stream) -> &'a str {
+    if enabled(stream) { pair.0 } else { pair.1 }
+}
+
+
+/// A control sequence, which is only written if color is enabled for a stream.
+#[derive(Clone, Copy, Debug)]
+pub struct Escape<T = &'static str>(pub T, pub Stream);
+
+impl<T: Display> Display for Escape<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if enabled(self.1) {
+            self.0.fmt(f)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+
+/// A pair of colored and plain strings, of which only one is written,
+///     depending on whether color is enabled for a stream.
+#[derive(Clone, Copy, Debug)]
+pub struct Choice(pub &'static str, pub &'static str, pub Stream);
+
+impl Display for Choice {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(select((self.0, self.1), self.2))
+    }
+}
+
+
+/// Styled text that is written by a closure each time it is displayed, rather
+///     than formatted in advance. This is the output of a Lazy Mode invocation.
+///
+/// With the `&` sigil, the closure borrows its arguments, and cannot outlive
+///     them. With `& move`, it takes ownership of them instead, so that it can
+///     be returned from the function that made it, or stored.
+/// ```
+/// use std::fmt::Display;
+/// use sgr_macros::red;
+///
+/// fn total(items: &[u32]) -> impl Display {
+///     let sum: u32 = items.iter().sum();
+///     red!(& move "{} items", sum)
+/// }
+///
+/// assert_eq!(total(&[1, 2, 3]).to_string(), "\x1B[31m6 items\x1B[39m");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Lazy<F>(F);
+
+impl<F: Fn(&mut Formatter) -> fmt::Result> Lazy<F> {
+    pub const fn new(write: F) -> Self { Self(write) }
+}
+
+impl<F: Fn(&mut Formatter) -> fmt::Result> Display for Lazy<F> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+impl<F: Fn(&mut Formatter) -> fmt::Result> Debug for Lazy<F> {
+    /// Write the text as a string literal, with its control sequences escaped.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        write!(Escaped(f), "{self}")?;
+        f.write_char('"')
+    }
+}
+
+
+/// A writer that escapes text as [`Debug`] does for a string, and forwards it
+///     to a formatter.
+struct Escaped<'a, 'b>(&'a mut Formatter<'b>);
+
+impl Write for Escaped<'_, '_> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        for c in text.chars() {
+            //  Unlike a character, a string does not escape single quotes.
+            match c {
+                '\'' => self.0.write_char(c)?,
+                c => write!(self.0, "{}", c.escape_debug())?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(
+        vars: &'a [(&str, &str)],
+    ) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |name| vars.iter()
+            .find(|(var, _)| *var == name)
+            .map(|(_, value)| OsString::from(value))
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(detect(env(&[]), true));
+        assert!(!detect(env(&[]), false));
+
+        assert!(!detect(env(&[("NO_COLOR", "1")]), true));
+        assert!(detect(env(&[("NO_COLOR", "")]), true));
+
+        assert!(detect(env(&[("CLICOLOR_FORCE", "1")]), false));
+        assert!(!detect(env(&[("CLICOLOR_FORCE", "0")]), false));
+        assert!(!detect(env(&[("CLICOLOR_FORCE", "")]), false));
+        assert!(!detect(
+            env(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]),
+            true,
+        ));
+
+        assert!(!detect(env(&[("TERM", "dumb")]), true));
+        assert!(detect(
+            env(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "1")]),
+            false,
+        ));
+        assert!(detect(env(&[("TERM", "xterm-256color")]), true));
+
+        assert!(!detect(env(&[("CLICOLOR", "0")]), true));
+        assert!(detect(env(&[("CLICOLOR", "1")]), true));
+        assert!(!detect(env(&[("CLICOLOR", "1")]), false));
+    }
+}
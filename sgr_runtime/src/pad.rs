@@ -0,0 +1,126 @@
+use std::fmt::{self, Display, Formatter, Write};
+use unicode_width::UnicodeWidthStr;
+
+
+/// Find the length of the control sequence at the start of a string, if there
+///     is one. This may be any CSI sequence, including SGR, or an operating
+///     system command, such as a hyperlink.
+#[doc(hidden)]
+pub fn escape_len(text: &str) -> Option<usize> {
+    if let Some(seq) = text.strip_prefix("\x1B[") {
+        //  Parameter bytes, then intermediate bytes, then one final byte.
+        let params = seq.find(|c| !matches!(c, '\x30'..='\x3F'))
+            .unwrap_or(seq.len());
+        let seq = &seq[params..];
+        let inter = seq.find(|c| !matches!(c, '\x20'..='\x2F'))?;
+
+        matches!(seq[inter..].chars().next()?, '\x40'..='\x7E')
+            .then_some(2 + params + inter + 1)
+    } else {
+        //  A command ends with a String Terminator, or with a BEL character.
+        let command = text.strip_prefix("\x1B]")?;
+        let end = command.find(['\x1B', '\x07'])?;
+
+        match &command[end..] {
+            s if s.starts_with("\x1B\\") => Some(2 + end + 2),
+            s if s.starts_with('\x07') => Some(2 + end + 1),
+            _ => None,
+        }
+    }
+}
+
+
+/// Find the number of columns that text occupies in a terminal. Control
+///     sequences take no space, and the width of each other character follows
+///     its Unicode East Asian Width.
+/// ```
+/// assert_eq!(sgr_runtime::width(sgr_macros::red!("日本")), 4);
+/// ```
+pub fn width(text: &str) -> usize {
+    let mut total = 0;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        match rest.find('\x1B') {
+            Some(start) => {
+                total += rest[..start].width();
+                rest = &rest[start..];
+
+                match escape_len(rest) {
+                    Some(len) => rest = &rest[len..],
+                    None => {
+                        //  A lone escape character has no width of its own.
+                        rest = &rest[1..];
+                    }
+                }
+            }
+            None => {
+                total += rest.width();
+                break;
+            }
+        }
+    }
+
+    total
+}
+
+
+/// The alignment of a [`Pad`].
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+
+/// A value, padded to a minimum width in columns. Unlike the padding of the
+///     standard formatting macros, control sequences are not counted, and wide
+///     characters are counted twice.
+///
+/// This is used by the macros of `sgr_macros` to pad colored text, but may also
+///     be used directly.
+/// ```
+/// use sgr_runtime::{Align, Pad};
+///
+/// let pad = Pad {
+///     value: sgr_macros::red!("ab"),
+///     width: 4,
+///     align: Align::Right,
+///     fill: '.',
+/// };
+///
+/// assert_eq!(pad.to_string(), "..\x1B[31mab\x1B[39m");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Pad<T> {
+    pub value: T,
+    pub width: usize,
+    pub align: Align,
+    pub fill: char,
+}
+
+impl<T: Display> Display for Pad<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let text = self.value.to_string();
+        let pad = self.width.saturating_sub(width(&text));
+        let (before, after) = match self.align {
+            Align::Left => (0, pad),
+            Align::Center => (pad / 2, pad - pad / 2),
+            Align::Right => (pad, 0),
+        };
+
+        for _ in 0..before {
+            f.write_char(self.fill)?;
+        }
+
+        f.write_str(&text)?;
+
+        for _ in 0..after {
+            f.write_char(self.fill)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt::{self, Display, Formatter};
+use crate::{pad::escape_len, Color};
+
+
+/// A style of underline.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Underline {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+
+/// A speed of blinking.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Blink {
+    #[default]
+    None,
+    /// Slow blinking, as opened by `sgr_blink!`.
+    Slow,
+    /// Rapid blinking, as opened by `sgr_blink2!`.
+    Rapid,
+}
+
+
+/// A vertical position of text.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Script {
+    #[default]
+    None,
+    Super,
+    Sub,
+}
+
+
+/// The rendition state of a terminal, following the SGR parameters opened and
+///     closed by the macros of `sgr_macros`. The default state has no style.
+///
+/// Bold and faint are separate, since a terminal may show both, but they are
+///     closed together. Bright colors are opened as basic colors, and closed
+///     by 39 and 49, like every other color.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct StyleState {
+    pub bold: bool,
+    pub faint: bool,
+    pub italic: bool,
+    pub underline: Underline,
+    pub blink: Blink,
+    pub invert: bool,
+    pub conceal: bool,
+    pub strike: bool,
+    pub overline: bool,
+    pub script: Script,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub uline_color: Option<Color>,
+}
+
+impl StyleState {
+    /// Check whether this state has no style.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the parameters of an SGR sequence, in order. A parameter that is
+    ///     not understood is skipped, and the first one is returned as an
+    ///     error after the rest are applied. A number that is not used by any
+    ///     macro, such as a font, is ignored.
+    pub(crate) fn apply(&mut self, params: &str) -> Result<(), String> {
+        let mut parts = params.split(';');
+        let mut error: Option<String> = None;
+
+        while let Some(part) = parts.next() {
+            let mut subs = part.split(':');
+            let code = match subs.next().unwrap_or_default() {
+                "" => 0,
+                code => match code.parse::<u16>() {
+                    Ok(code) => code,
+                    Err(..) => {
+                        error.get_or_insert_with(|| part.into());
+                        continue;
+                    }
+                },
+            };
+
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.faint = true,
+                3 => self.italic = true,
+                4 => self.underline = match subs.next() {
+                    None | Some("1") => Underline::Single,
+                    Some("0") => Underline::None,
+                    Some("2") => Underline::Double,
+                    Some("3") => Underline::Curly,
+                    Some("4") => Underline::Dotted,
+                    Some("5") => Underline::Dashed,
+                    Some(..) => {
+                        error.get_or_insert_with(|| part.into());
+                        Underline::Single
+                    }
+                },
+                5 => self.blink = Blink::Slow,
+                6 => self.blink = Blink::Rapid,
+                7 => self.invert = true,
+                8 => self.conceal = true,
+                9 => self.strike = true,
+                21 => self.underline = Underline::Double,
+                22 => (self.bold, self.faint) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = Underline::None,
+                25 => self.blink = Blink::None,
+                27 => self.invert = false,
+                28 => self.conceal = false,
+                29 => self.strike = false,
+                30..=37 => self.fg = Some(basic(code - 30)),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some(basic(code - 40)),
+                49 => self.bg = None,
+                53 => self.overline = true,
+                55 => self.overline = false,
+                59 => self.uline_color = None,
+                73 => self.script = Script::Super,
+                74 => self.script = Script::Sub,
+                75 => self.script = Script::None,
+                90..=97 => self.fg = Some(basic(code - 90 + 8)),
+                100..=107 => self.bg = Some(basic(code - 100 + 8)),
+                38 | 48 | 58 => {
+                    //  An extended color takes its arguments either from its
+                    //      own subparameters, or from the parameters after it.
+                    let args: Vec<&str> = match subs.next() {
+                        Some(kind) => std::iter::once(kind).chain(subs)
+                            .collect(),
+                        None => match parts.clone().next() {
+                            Some("5") => parts.by_ref().take(2).collect(),
+                            Some("2") => parts.by_ref().take(4).collect(),
+                            _ => Vec::new(),
+                        },
+                    };
+
+                    let color = extended(&args);
+
+                    if color.is_none() {
+                        let arg: Vec<&str> = [part].into_iter().chain(args)
+                            .collect();
+                        error.get_or_insert_with(|| arg.join(";"));
+                    }
+
+                    match code {
+                        38 => self.fg = color,
+                        48 => self.bg = color,
+                        _ => self.uline_color = color,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        error.map_or(Ok(()), Err)
+    }
+}
+
+
+/// Find a basic color by its index, from 0 to 15.
+pub(crate) fn basic(index: u16) -> Color {
+    const COLORS: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BlackBright,
+        Color::RedBright,
+        Color::GreenBright,
+        Color::YellowBright,
+        Color::BlueBright,
+        Color::MagentaBright,
+        Color::CyanBright,
+        Color::WhiteBright,
+    ];
+
+    COLORS[usize::from(index)]
+}
+
+
+/// Read the arguments of an extended color, such as `5;n` or `2;r;g;b`. With
+///     subparameters, an RGB color may have an empty color space ID before its
+///     channels, as in `38:2::r:g:b`.
+fn extended(args: &[&str]) -> Option<Color> {
+    let num = |s: &&str| s.parse::<u8>().ok();
+
+    match args {
+        ["5", n] => Some(Color::Indexed(num(n)?)),
+        ["2", _, r, g, b] | ["2", r, g, b] => {
+            Some(Color::Rgb(num(r)?, num(g)?, num(b)?))
+        }
+        _ => None,
+    }
+}
+
+
+/// A reason that text could not be parsed.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// An escape character that does not begin a complete CSI sequence or
+    ///     operating system command.
+    Incomplete,
+    /// A parameter of an SGR sequence that is out of range, or an extended
+    ///     color without valid arguments.
+    Parameter(String),
+}
+
+
+/// An error found while parsing text, at a byte offset of the escape character
+///     that begins the malformed sequence.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub kind: ErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Incomplete => write!(
+                f, "incomplete control sequence at byte {}", self.offset,
+            ),
+            ErrorKind::Parameter(param) => write!(
+                f, "invalid SGR parameter `{param}` at byte {}", self.offset,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+
+/// Split text with SGR sequences, such as the output of the macros of
+///     `sgr_macros`, into runs of text with the style that each is shown in.
+///     Adjacent runs with the same style are joined, and empty runs are left
+///     out.
+///
+/// Other complete control sequences, such as hyperlinks, are removed. A
+///     malformed sequence is an error, as is an SGR parameter that is not
+///     understood.
+/// ```
+/// use sgr_runtime::{parse, Color, StyleState};
+///
+/// let bold = StyleState { bold: true, ..StyleState::default() };
+/// let red = StyleState { fg: Some(Color::Red), ..bold };
+///
+/// assert_eq!(
+///     parse(sgr_macros::sgr_bold!("a ", sgr_macros::red!("b"))).unwrap(),
+///     [(String::from("a "), bold), (String::from("b"), red)],
+/// );
+/// assert!(parse("\x1B[38;5m").is_err());
+/// ```
+pub fn parse(text: &str) -> Result<Vec<(String, StyleState)>, ParseError> {
+    let mut runs: Vec<(String, StyleState)> = Vec::new();
+    let mut state = StyleState::default();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let start = rest.find('\x1B').unwrap_or(rest.len());
+        let (plain, tail) = rest.split_at(start);
+
+        match runs.last_mut() {
+            _ if plain.is_empty() => {}
+            Some((run, last)) if *last == state => run.push_str(plain),
+            _ => runs.push((String::from(plain), state)),
+        }
+
+        if tail.is_empty() {
+            break;
+        }
+
+        let offset = text.len() - tail.len();
+        let error = |kind| ParseError { offset, kind };
+
+        let len = escape_len(tail)
+            .ok_or_else(|| error(ErrorKind::Incomplete))?;
+        let seq = &tail[..len];
+        rest = &tail[len..];
+
+        //  A sequence with private or intermediate bytes is not SGR.
+        let sgr = seq.strip_prefix("\x1B[")
+            .and_then(|seq| seq.strip_suffix('m'))
+            .filter(|p| p.chars().all(|c| "0123456789:;".contains(c)));
+
+        if let Some(params) = sgr {
+            state.apply(params)
+                .map_err(|param| error(ErrorKind::Parameter(param)))?;
+        }
+    }
+
+    Ok(runs)
+}
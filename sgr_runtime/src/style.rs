@@ -0,0 +1,375 @@
+use std::fmt::{self, Display, Formatter, Write};
+use crate::{enabled, Stream};
+
+
+/// A color, in any of the forms accepted by the macros of `sgr_macros`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BlackBright,
+    RedBright,
+    GreenBright,
+    YellowBright,
+    BlueBright,
+    MagentaBright,
+    CyanBright,
+    WhiteBright,
+    /// An 8-bit indexed color, as used by `color_256!`.
+    Indexed(u8),
+    /// A 24-bit color, as used by `color_rgb!`.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Get the palette index of a basic color, from 0 to 15.
+    const fn basic_index(&self) -> Option<u8> {
+        Some(match self {
+            Self::Black => 0,
+            Self::Red => 1,
+            Self::Green => 2,
+            Self::Yellow => 3,
+            Self::Blue => 4,
+            Self::Magenta => 5,
+            Self::Cyan => 6,
+            Self::White => 7,
+            Self::BlackBright => 8,
+            Self::RedBright => 9,
+            Self::GreenBright => 10,
+            Self::YellowBright => 11,
+            Self::BlueBright => 12,
+            Self::MagentaBright => 13,
+            Self::CyanBright => 14,
+            Self::WhiteBright => 15,
+            Self::Indexed(..) | Self::Rgb(..) => return None,
+        })
+    }
+
+    /// Write the opening parameters of this color, for a layer with the given
+    ///     base parameter: 38 for foreground, 48 for background, or 58 for
+    ///     underline.
+    fn fmt_opening(&self, f: &mut impl Write, base: u8) -> fmt::Result {
+        match (self.basic_index(), self) {
+            //  Underline color has no basic color parameters, but the first 16
+            //      indexed colors are the same.
+            (Some(index), _) if base == 58 => write!(f, "58;5;{index}"),
+            (Some(index @ 0..=7), _) => write!(f, "{}", base - 8 + index),
+            (Some(index), _) => write!(f, "{}", base + 52 + index - 8),
+            (None, Self::Indexed(index)) => write!(f, "{base};5;{index}"),
+            (None, Self::Rgb(r, g, b)) => write!(f, "{base};2;{r};{g};{b}"),
+            (None, _) => unreachable!(),
+        }
+    }
+}
+
+
+
+/// The 16 basic colors, as defined by xterm. Many terminals use different
+///     values for these, but this is the most common reference.
+const BASIC: [u32; 16] = [
+    0x000000, 0xCD0000, 0x00CD00, 0xCDCD00,
+    0x0000EE, 0xCD00CD, 0x00CDCD, 0xE5E5E5,
+    0x7F7F7F, 0xFF0000, 0x00FF00, 0xFFFF00,
+    0x5C5CFF, 0xFF00FF, 0x00FFFF, 0xFFFFFF,
+];
+
+/// The channel levels of the 6×6×6 color cube, at indices 16 through 231.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+
+/// Get the red, green and blue values of a color in the xterm 256-color
+///     palette.
+pub const fn xterm_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => {
+            let [_, r, g, b] = BASIC[index as usize].to_be_bytes();
+            (r, g, b)
+        }
+        16..=231 => {
+            let i = (index - 16) as usize;
+            (CUBE[i / 36], CUBE[i / 6 % 6], CUBE[i % 6])
+        }
+        232..=255 => {
+            let v = 8 + 10 * (index - 232);
+            (v, v, v)
+        }
+    }
+}
+
+/// A text attribute, corresponding to one of the style macros of `sgr_macros`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Attr {
+    Bold,
+    Faint,
+    Italic,
+    Uline,
+    UlineDouble,
+    UlineCurly,
+    UlineDotted,
+    UlineDashed,
+    Blink,
+    Blink2,
+    Invert,
+    Conceal,
+    Strike,
+    Super,
+    Sub,
+}
+
+impl Attr {
+    /// Every attribute, in the order that they are applied by a [`Style`].
+    pub const ALL: [Self; 15] = [
+        Self::Bold,
+        Self::Faint,
+        Self::Italic,
+        Self::Uline,
+        Self::UlineDouble,
+        Self::UlineCurly,
+        Self::UlineDotted,
+        Self::UlineDashed,
+        Self::Blink,
+        Self::Blink2,
+        Self::Invert,
+        Self::Conceal,
+        Self::Strike,
+        Self::Super,
+        Self::Sub,
+    ];
+
+    /// Get the opening parameter of this attribute.
+    pub const fn opening(&self) -> &'static str {
+        match self {
+            Self::Bold => "1",
+            Self::Faint => "2",
+            Self::Italic => "3",
+            Self::Uline => "4",
+            Self::UlineDouble => "4:2",
+            Self::UlineCurly => "4:3",
+            Self::UlineDotted => "4:4",
+            Self::UlineDashed => "4:5",
+            Self::Blink => "5",
+            Self::Blink2 => "6",
+            Self::Invert => "7",
+            Self::Conceal => "8",
+            Self::Strike => "9",
+            Self::Super => "73",
+            Self::Sub => "74",
+        }
+    }
+
+    /// Get the closing parameter of this attribute.
+    pub const fn closing(&self) -> &'static str {
+        match self {
+            Self::Bold | Self::Faint => "22",
+            Self::Italic => "23",
+            Self::Uline
+            | Self::UlineDouble
+            | Self::UlineCurly
+            | Self::UlineDotted
+            | Self::UlineDashed => "24",
+            Self::Blink | Self::Blink2 => "25",
+            Self::Invert => "27",
+            Self::Conceal => "28",
+            Self::Strike => "29",
+            Self::Super | Self::Sub => "75",
+        }
+    }
+
+    const fn bit(&self) -> u16 { 1 << *self as u16 }
+}
+
+
+/// What is reverted at the end of styled text, as with the `!` and `*` sigils
+///     of `sgr_macros`.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub enum Revert {
+    /// Revert only the parameters of the style. This is the default.
+    #[default]
+    One,
+    /// Revert all formatting.
+    All,
+    /// Revert nothing.
+    None,
+}
+
+
+/// A set of colors and attributes, chosen at runtime, which produces the same
+///     output as the equivalent macros.
+///
+/// Attributes are applied in the order of [`Attr::ALL`], followed by the
+///     foreground, background, and underline colors. This is the same as the
+///     output of the `sgr!` macro, given attributes in the same order.
+/// ```
+/// use sgr_runtime::{Attr, Color, Style};
+///
+/// let style = Style::new().attr(Attr::Bold).fg(Color::Red);
+///
+/// assert_eq!(
+///     style.paint("Bold Red").to_string(),
+///     sgr_macros::sgr!(bold, red; "Bold Red"),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Style {
+    attrs: u16,
+    fg: Option<Color>,
+    bg: Option<Color>,
+    uline_color: Option<Color>,
+    revert: Revert,
+    gate: Option<Stream>,
+}
+
+impl Style {
+    /// Create a style with no colors or attributes.
+    pub const fn new() -> Self {
+        Self {
+            attrs: 0,
+            fg: None,
+            bg: None,
+            uline_color: None,
+            revert: Revert::One,
+            gate: None,
+        }
+    }
+
+    /// Add an attribute.
+    pub const fn attr(mut self, attr: Attr) -> Self {
+        self.attrs |= attr.bit();
+        self
+    }
+
+    /// Set the foreground color.
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set the background color.
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Set the underline color.
+    pub const fn uline_color(mut self, color: Color) -> Self {
+        self.uline_color = Some(color);
+        self
+    }
+
+    /// Set what is reverted at the end of styled text.
+    pub const fn revert(mut self, revert: Revert) -> Self {
+        self.revert = revert;
+        self
+    }
+
+    /// Only write control sequences if color is enabled for a stream, as with
+    ///     the `?` sigil of `sgr_macros`.
+    pub const fn gate(mut self, stream: Stream) -> Self {
+        self.gate = Some(stream);
+        self
+    }
+
+    /// Check whether this style has an attribute.
+    pub const fn has(&self, attr: Attr) -> bool {
+        self.attrs & attr.bit() != 0
+    }
+
+    /// Check whether this style has no colors or attributes.
+    pub const fn is_empty(&self) -> bool {
+        self.attrs == 0
+            && self.fg.is_none()
+            && self.bg.is_none()
+            && self.uline_color.is_none()
+    }
+
+    fn colors(&self) -> impl Iterator<Item = (Color, u8)> {
+        [(self.fg, 38), (self.bg, 48), (self.uline_color, 58)].into_iter()
+            .filter_map(|(color, base)| Some((color?, base)))
+    }
+
+    fn attrs(&self) -> impl Iterator<Item = Attr> + '_ {
+        Attr::ALL.into_iter().filter(|attr| self.has(*attr))
+    }
+
+    /// Format the parameters that open this style, separated by semicolons.
+    pub fn opening(&self) -> String {
+        let mut params = String::new();
+
+        for attr in self.attrs() {
+            params.push_str(attr.opening());
+            params.push(';');
+        }
+
+        for (color, base) in self.colors() {
+            //  Writing to a `String` cannot fail.
+            let _ = color.fmt_opening(&mut params, base);
+            params.push(';');
+        }
+
+        params.pop();
+        params
+    }
+
+    /// Format the parameters that revert this style, separated by semicolons.
+    ///     Parameters shared by several attributes are only included once.
+    pub fn closing(&self) -> String {
+        let mut params: Vec<String> = Vec::new();
+
+        let attrs = self.attrs().map(|attr| String::from(attr.closing()));
+
+        //  Every color of a layer, bright or not, is closed by the parameter
+        //      after its base: 39, 49, or 59.
+        let colors = self.colors().map(|(_, base)| (base + 1).to_string());
+
+        for param in attrs.chain(colors) {
+            if !params.contains(&param) {
+                params.push(param);
+            }
+        }
+
+        params.join(";")
+    }
+
+    /// Apply this style to a value, which will be wrapped in control
+    ///     sequences when it is displayed.
+    pub fn paint<T: Display>(&self, value: T) -> Painted<T> {
+        Painted { style: *self, value }
+    }
+}
+
+
+/// A value with a [`Style`] applied to it.
+#[derive(Clone, Copy, Debug)]
+pub struct Painted<T> {
+    style: Style,
+    value: T,
+}
+
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let style = &self.style;
+        let colored = !style.is_empty() && style.gate.is_none_or(enabled);
+
+        if colored {
+            write!(f, "\x1B[{}m", style.opening())?;
+        }
+
+        self.value.fmt(f)?;
+
+        if colored {
+            match style.revert {
+                Revert::One => write!(f, "\x1B[{}m", style.closing())?,
+                Revert::All => f.write_str("\x1B[m")?,
+                Revert::None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Every style macro, checked against the opening and closing parameters given
+//!     for it by ECMA-48, or by xterm for parameters that ECMA-48 does not
+//!     define, and against a model of the rendition state of a terminal.
+
+use sgr_macros::*;
+use sgr_runtime::{parse, StyleState};
+
+
+/// The outputs of a macro in each reversion mode, with the name of the macro,
+///     and the parameters that should open and close it.
+struct Entry {
+    name: &'static str,
+    outputs: [&'static str; 3],
+    opening: &'static str,
+    closing: &'static str,
+}
+
+macro_rules! matrix {
+    ($($mac:ident => $opening:literal, $closing:literal;)*) => {
+        /// Every macro defined with a fixed pair of parameters.
+        const MATRIX: &[Entry] = &[$(Entry {
+            name: stringify!($mac),
+            outputs: [$mac!("text"), $mac!(* "text"), $mac!(! "text")],
+            opening: $opening,
+            closing: $closing,
+        }),*];
+
+        /// Every macro nested within every other, as `outer!("x",
+        ///     inner!("y"), "z")`, with the names of both macros.
+        fn nested() -> Vec<(&'static str, &'static str, &'static str)> {
+            let mut nested = Vec::new();
+            matrix!(@outer nested [$($mac)*] [$($mac)*]);
+            nested
+        }
+    };
+    (@outer $vec:ident [$($outer:ident)*] $inner:tt) => {
+        $(matrix!(@inner $vec $outer $inner);)*
+    };
+    (@inner $vec:ident $outer:ident [$($inner:ident)*]) => {
+        $vec.extend([$((
+            stringify!($outer),
+            stringify!($inner),
+            $outer!("x", $inner!("y"), "z"),
+        )),*]);
+    };
+}
+
+matrix! {
+    sgr_bold => "1", "22";
+    sgr_faint => "2", "22";
+    sgr_italic => "3", "23";
+    sgr_uline => "4", "24";
+    sgr_uline_double => "4:2", "24";
+    sgr_uline_curly => "4:3", "24";
+    sgr_uline_dotted => "4:4", "24";
+    sgr_uline_dashed => "4:5", "24";
+    sgr_blink => "5", "25";
+    sgr_blink2 => "6", "25";
+    sgr_invert => "7", "27";
+    sgr_conceal => "8", "28";
+    sgr_strike => "9", "29";
+    sgr_super => "73", "75";
+    sgr_sub => "74", "75";
+
+    black => "30", "39";
+    red => "31", "39";
+    green => "32", "39";
+    yellow => "33", "39";
+    blue => "34", "39";
+    magenta => "35", "39";
+    cyan => "36", "39";
+    white => "37", "39";
+    black_bright => "90", "39";
+    red_bright => "91", "39";
+    green_bright => "92", "39";
+    yellow_bright => "93", "39";
+    blue_bright => "94", "39";
+    magenta_bright => "95", "39";
+    cyan_bright => "96", "39";
+    white_bright => "97", "39";
+
+    black_bg => "40", "49";
+    red_bg => "41", "49";
+    green_bg => "42", "49";
+    yellow_bg => "43", "49";
+    blue_bg => "44", "49";
+    magenta_bg => "45", "49";
+    cyan_bg => "46", "49";
+    white_bg => "47", "49";
+    black_bright_bg => "100", "49";
+    red_bright_bg => "101", "49";
+    green_bright_bg => "102", "49";
+    yellow_bright_bg => "103", "49";
+    blue_bright_bg => "104", "49";
+    magenta_bright_bg => "105", "49";
+    cyan_bright_bg => "106", "49";
+    white_bright_bg => "107", "49";
+}
+
+
+define_style! {
+    notice = bold + cyan_bright + bg(0x202020);
+}
+
+/// Macros whose parameters are given in their input, with the parameters that
+///     should open and close them.
+const COMPOSITE: [(&str, &str, &str); 9] = [
+    (color_256!(173; "text"), "38;5;173", "39"),
+    (color_256_bg!(64; "text"), "48;5;64", "49"),
+    (color_rgb!(0x112233; "text"), "38;2;17;34;51", "39"),
+    (color_rgb_bg!(0x445566; "text"), "48;2;68;85;102", "49"),
+    (uline_color_256!(160; "text"), "58;5;160", "59"),
+    (uline_color_rgb!(0x010203; "text"), "58;2;1;2;3", "59"),
+    (
+        sgr!(bold, red_bright, bg = 0x223344; "text"),
+        "1;91;48;2;34;51;68",
+        "22;39;49",
+    ),
+    (notice!("text"), "1;96;48;2;32;32;32", "22;39;49"),
+    (sgr_markup!("<b><red_bright>text</red_bright></b>"), "1;91", "39;22"),
+];
+
+
+/// Find the state of each run of text, which must be `expected`.
+fn states<const N: usize>(text: &str, expected: [&str; N]) -> [StyleState; N] {
+    let runs = parse(text).unwrap();
+    let mut states = [StyleState::default(); N];
+    let mut i = 0;
+
+    //  Runs in the same state have been joined, so each may hold several of
+    //      the expected texts.
+    for (run, state) in runs {
+        let mut rest = run.as_str();
+
+        while !rest.is_empty() {
+            rest = rest.strip_prefix(expected[i]).expect(text);
+            states[i] = state;
+            i += 1;
+        }
+    }
+
+    assert_eq!(i, N, "{text:?}");
+    states
+}
+
+
+#[test]
+fn test_conformance_pairs() {
+    for entry in MATRIX {
+        let Entry { name, outputs: [one, all, none], opening, closing } = entry;
+
+        let expected = format!("\x1B[{opening}mtext\x1B[{closing}m");
+
+        assert_eq!(*one, expected, "{name}");
+        assert_eq!(*all, format!("\x1B[{opening}mtext\x1B[m"), "{name}");
+        assert_eq!(*none, format!("\x1B[{opening}mtext"), "{name}");
+    }
+
+    for (output, opening, closing) in COMPOSITE {
+        assert_eq!(output, format!("\x1B[{opening}mtext\x1B[{closing}m"));
+    }
+}
+
+
+#[test]
+fn test_conformance_reverted() {
+    let outputs = MATRIX.iter()
+        .flat_map(|entry| &entry.outputs[..2])
+        .chain(COMPOSITE.iter().map(|(output, _, _)| output))
+        .chain([
+            &sgr_gradient!(red -> dodgerblue; "text"),
+            &sgr_gradient!(red -> blue, bg, color_256; * "text"),
+            &hyperlink!("https://docs.rs"; red_bright!("text")),
+        ]);
+
+    for output in outputs {
+        //  The style is opened, and then fully closed.
+        let mut runs = parse(&format!("{output}x")).unwrap();
+        let after = runs.pop();
+
+        assert_eq!(after, Some((String::from("x"), StyleState::default())));
+        assert!(runs.iter().all(|(_, state)| !state.is_empty()), "{output:?}");
+    }
+
+    //  A style left open is not closed.
+    for entry in MATRIX {
+        let [styled, after] = states(&format!("{}x", entry.outputs[2]), [
+            "text",
+            "x",
+        ]);
+
+        assert_eq!(styled, after, "{}", entry.name);
+    }
+}
+
+
+#[test]
+fn test_conformance_nested() {
+    //  Whatever the inner style closes, the outer style is in effect again
+    //      after it, and nothing else is.
+    for (outer, inner, output) in nested() {
+        let [x, y, z] = states(output, ["x", "y", "z"]);
+
+        assert_ne!(x, StyleState::default(), "{inner} in {outer}");
+        assert_eq!(x, z, "{inner} in {outer}");
+
+        //  The inner style is shown, unless it is the outer style.
+        if outer != inner {
+            assert_ne!(x, y, "{inner} in {outer}");
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use sgr_macros::*;
+use sgr_runtime::{html, Markup};
+
+
+/// Every style macro, with its declaration in a `style` attribute, and its
+///     class.
+const ATTRS: [([&str; 3], &str, &str); 15] = {
+    macro_rules! table {
+        ($($mac:ident => $css:literal, $class:literal),* $(,)?) => {
+            [$((
+                [$mac!("text"), $mac!(* "text"), $mac!(! "text")],
+                $css,
+                $class,
+            )),*]
+        };
+    }
+
+    table![
+        sgr_bold => "font-weight:bold", "sgr-bold",
+        sgr_faint => "opacity:0.5", "sgr-faint",
+        sgr_italic => "font-style:italic", "sgr-italic",
+        sgr_uline => "text-decoration:underline", "sgr-uline",
+        sgr_uline_double => "text-decoration:underline double",
+            "sgr-uline-double",
+        sgr_uline_curly => "text-decoration:underline wavy", "sgr-uline-curly",
+        sgr_uline_dotted => "text-decoration:underline dotted",
+            "sgr-uline-dotted",
+        sgr_uline_dashed => "text-decoration:underline dashed",
+            "sgr-uline-dashed",
+        sgr_blink => "text-decoration:blink", "sgr-blink",
+        sgr_blink2 => "text-decoration:blink", "sgr-blink",
+        sgr_invert => "color:Canvas;background-color:CanvasText",
+            "sgr-invert",
+        sgr_conceal => "visibility:hidden", "sgr-conceal",
+        sgr_strike => "text-decoration:line-through", "sgr-strike",
+        sgr_super => "vertical-align:super", "sgr-super",
+        sgr_sub => "vertical-align:sub", "sgr-sub",
+    ]
+};
+
+/// Colors, with their declarations in a `style` attribute, and their classes.
+const COLORS: [(&str, &str, &str); 14] = [
+    (black!("text"), "color:#000000", "sgr-fg-0"),
+    (red!("text"), "color:#cd0000", "sgr-fg-1"),
+    (white!("text"), "color:#e5e5e5", "sgr-fg-7"),
+    (black_bright!("text"), "color:#7f7f7f", "sgr-fg-8"),
+    (blue_bright!("text"), "color:#5c5cff", "sgr-fg-12"),
+    (green_bg!("text"), "background-color:#00cd00", "sgr-bg-2"),
+    (white_bright_bg!("text"), "background-color:#ffffff", "sgr-bg-15"),
+    (color_256!(3; "text"), "color:#cdcd00", "sgr-fg-3"),
+    (color_256!(173; "text"), "color:#d7875f", "sgr-fg-173"),
+    (color_256_bg!(232; "text"), "background-color:#080808", "sgr-bg-232"),
+    (color_256_bg!(255; "text"), "background-color:#eeeeee", "sgr-bg-255"),
+    (uline_color_256!(9; "text"), "text-decoration-color:#ff0000", "sgr-ul-9"),
+    (
+        uline_color_rgb!(0x123456; "text"),
+        "text-decoration-color:#123456",
+        "",
+    ),
+    (color_rgb!((255, 127, 63); "text"), "color:#ff7f3f", ""),
+];
+
+
+fn span(class: &str, style: &str, text: &str) -> String {
+    match (class, style) {
+        ("", style) => format!("<span style=\"{style}\">{text}</span>"),
+        (class, "") => format!("<span class=\"{class}\">{text}</span>"),
+        (class, style) => format!(
+            "<span class=\"{class}\" style=\"{style}\">{text}</span>",
+        ),
+    }
+}
+
+
+/// Recover the text of some HTML, by removing its tags and entities.
+fn text(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[rest[start..].find('>').unwrap() + start + 1..];
+    }
+
+    out.push_str(rest);
+    out.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+
+#[test]
+fn test_html_attrs() {
+    for (outputs, css, class) in ATTRS {
+        for output in outputs {
+            assert_eq!(html(output, Markup::Inline), span("", css, "text"));
+            assert_eq!(html(output, Markup::Classes), span(class, "", "text"));
+        }
+    }
+
+    //  Lines are drawn together.
+    assert_eq!(
+        html(sgr!(uline_curly, strike; "text"), Markup::Inline),
+        span("", "text-decoration:line-through underline wavy", "text"),
+    );
+    assert_eq!(
+        html(sgr!(uline, strike; "text"), Markup::Classes),
+        span("sgr-strike sgr-uline", "", "text"),
+    );
+}
+
+
+#[test]
+fn test_html_colors() {
+    for (output, css, class) in COLORS {
+        assert_eq!(html(output, Markup::Inline), span("", css, "text"));
+
+        //  RGB colors have no class.
+        let (class, css) = match class {
+            "" => ("", css),
+            class => (class, ""),
+        };
+        assert_eq!(html(output, Markup::Classes), span(class, css, "text"));
+    }
+
+    //  Inverted colors are swapped.
+    assert_eq!(
+        html(sgr!(invert, red, bg = 0x0000FF; "text"), Markup::Inline),
+        span("", "color:#0000ff;background-color:#cd0000", "text"),
+    );
+    assert_eq!(
+        html(sgr!(invert, red; "text"), Markup::Classes),
+        span("sgr-invert sgr-fg-1", "", "text"),
+    );
+
+    //  Colors may be given with subparameters, which the macros do not emit.
+    assert_eq!(
+        html("\x1B[38:2::1:2:3mtext", Markup::Inline),
+        span("", "color:#010203", "text"),
+    );
+    assert_eq!(
+        html("\x1B[48:5:1mtext", Markup::Inline),
+        span("", "background-color:#cd0000", "text"),
+    );
+}
+
+
+#[test]
+fn test_html_resets() {
+    //  Bold and faint share a closing parameter.
+    assert_eq!(
+        html(sgr_bold!("a ", sgr_faint!("b"), " c"), Markup::Classes),
+        [
+            span("sgr-bold", "", "a "),
+            span("sgr-bold sgr-faint", "", "b"),
+            span("sgr-bold", "", " c"),
+        ].concat(),
+    );
+
+    //  So do all foreground colors, including bright colors.
+    assert_eq!(
+        html(red!("a ", green_bright!("b"), " c"), Markup::Classes),
+        [
+            span("sgr-fg-1", "", "a "),
+            span("sgr-fg-10", "", "b"),
+            span("sgr-fg-1", "", " c"),
+        ].concat(),
+    );
+    assert_eq!(
+        html(blue_bg!("a ", color_256_bg!(64; "b"), " c"), Markup::Inline),
+        [
+            span("", "background-color:#0000ee", "a "),
+            span("", "background-color:#5f8700", "b"),
+            span("", "background-color:#0000ee", " c"),
+        ].concat(),
+    );
+
+    //  A total reset clears everything, and the outer style is restored.
+    assert_eq!(
+        html(sgr_italic!("a ", sgr!(bold, red; * "b"), " c"), Markup::Classes),
+        [
+            span("sgr-italic", "", "a "),
+            span("sgr-bold sgr-italic sgr-fg-1", "", "b"),
+            span("sgr-italic", "", " c"),
+        ].concat(),
+    );
+    assert_eq!(
+        html(&sgr_bold!(@ "a {}b", sgr_reset!()), Markup::Classes),
+        [span("sgr-bold", "", "a "), String::from("b")].concat(),
+    );
+
+    //  Styles that are never closed end with the HTML.
+    assert_eq!(
+        html(red!(! "a"), Markup::Classes),
+        span("sgr-fg-1", "", "a"),
+    );
+}
+
+
+#[test]
+fn test_html_text() {
+    //  Text is escaped, and other control sequences are removed.
+    assert_eq!(
+        html("<a href='x'>&\"\x1B[2K\x1B]0;title\x07", Markup::Inline),
+        "&lt;a href=&#39;x&#39;&gt;&amp;&quot;",
+    );
+    assert_eq!(html("a\x1Bb", Markup::Inline), "ab");
+
+    //  Sequences with no text between them do not make an empty span.
+    assert_eq!(
+        html(concat!(red!(""), green!("x"), blue!("")), Markup::Classes),
+        span("sgr-fg-2", "", "x"),
+    );
+
+    //  Hyperlinks are anchors, which may contain spans.
+    assert_eq!(
+        html(
+            red!(concat!("a ", hyperlink!("https://a.b/?x&y"; blue!("b")))),
+            Markup::Classes,
+        ),
+        [
+            span("sgr-fg-1", "", "a "),
+            String::from("<a href=\"https://a.b/?x&amp;y\">"),
+            span("sgr-fg-4", "", "b"),
+            String::from("</a>"),
+        ].concat(),
+    );
+    assert_eq!(
+        html(&hyperlink!("https://a.b"; @ "{}", 1), Markup::Inline),
+        "<a href=\"https://a.b\">1</a>",
+    );
+
+    //  Only links with a known scheme are anchors, so that untrusted text
+    //      cannot run a script. The text of any other link is kept.
+    let link = |uri: &str| format!("\x1B]8;;{uri}\x1B\\x\x1B]8;;\x1B\\");
+
+    for uri in ["http://a.b", "HTTPS://a.b", "file:///a", "mailto:a@b.c"] {
+        let expected = format!("<a href=\"{uri}\">x</a>");
+        assert_eq!(html(&link(uri), Markup::Inline), expected);
+    }
+
+    for uri in [
+        "javascript:alert(1)",
+        "JavaScript:alert(1)",
+        " javascript:alert(1)",
+        "java\tscript:alert(1)",
+        "data:text/html,<script>alert(1)</script>",
+        "vbscript:msgbox(1)",
+        "a.b/c",
+    ] {
+        assert_eq!(html(&link(uri), Markup::Inline), "x", "{uri}");
+    }
+
+    assert_eq!(
+        html(
+            &format!("{}{}", link("javascript:alert(1)"), link("https://a.b")),
+            Markup::Inline,
+        ),
+        "x<a href=\"https://a.b\">x</a>",
+    );
+}
+
+
+#[test]
+fn test_html_roundtrip() {
+    //  The output of the macro and of the function are the same, and the text
+    //      of either is the text of the input.
+    macro_rules! check {
+        ($($input:expr),* $(,)?) => {$(
+            let input: &str = $input;
+            let inline = sgr_html!($input);
+            let classes = sgr_html!(classes; $input);
+
+            assert_eq!(inline, html(input, Markup::Inline));
+            assert_eq!(classes, html(input, Markup::Classes));
+            assert_eq!(text(inline), sgr_strip!($input));
+            assert_eq!(text(classes), sgr_strip!($input));
+        )*};
+    }
+
+    check![
+        green!("green text"),
+        green!(! "green text non-reverted"),
+        sgr_bold!(green!("Bold Green Text")),
+        concat!(sgr_bold!("Bold Text"), ", Normal Text"),
+        color_rgb!(0x420311; "Maroon Text"),
+        color_rgb_bg!(0x420311; "Text on Maroon"),
+        color_rgb!(steelblue; "text"),
+        color_rgb!(hsl(210, 40%, 50%); "text"),
+        color_256!(173; "text"),
+        color_256_bg!(64; "text"),
+        uline_color_256!(160; sgr_uline_curly!("Misspelled")),
+        uline_color_rgb!(red; sgr_uline_dotted!("text")),
+        sgr_uline!(concat!("a ", sgr_uline_curly!("b"), " c")),
+        sgr!(bold, italic, fg = 0x112233, bg = 0x445566; "text"),
+        sgr!(uline_curly, ul = red, uline_color_256 = 4; "text"),
+        sgr_bold!(sgr_italic!(sgr_uline!("WHAM"))),
+        sgr_bold!(* sgr_italic!(! sgr_uline!(! "WHAM"))),
+        red!(concat!("a ", green!("b"), " c")),
+        sgr_bold!("a ", sgr_faint!("b"), " c"),
+        red_bg!("a ", color_256_bg!(64; "b"), " c"),
+        red!("a ", sgr_bold!("b"), " c"),
+        red!("a ", green!(! "b"), " c"),
+        sgr_italic!("a ", green!(* "b"), " c"),
+        red!("a ", sgr_bold!("b ", green!("c")), " d"),
+        sgr!(bold, blue; "a ", green!("b"), " c"),
+        sgr_markup!("<b>bold</b> <red>a <blue_bg>b</blue_bg> c</red> <<"),
+        sgr_markup!("<rgb=#ABC>a</rgb> <256_bg=64><invert>b</invert></256_bg>"),
+        hyperlink!("https://docs.rs"; "Docs"),
+        hyperlink!("https://docs.rs", id = "d"; blue!("Docs")),
+        red!(concat!("See ", hyperlink!("https://docs.rs"; blue!("Docs")))),
+        sgr_gradient!(red -> blue; "gradient"),
+        sgr_gradient!(red -> lime -> blue, bg, color_256; "a b c"),
+        concat!("a", sgr_reset!(), "<&>"),
+        sgr_super!("x", sgr_sub!("2")),
+        sgr_blink2!(sgr_conceal!("x"), sgr_strike!("y")),
+        "\x1B[2K\x1B]8;;https://a.b\x07link\x1B]8;;\x07",
+    ];
+}
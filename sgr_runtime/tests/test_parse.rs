@@ -0,0 +1,401 @@
+use quickcheck::{quickcheck, Arbitrary, Gen};
+use sgr_macros::*;
+use sgr_runtime::*;
+
+
+/// The basic colors, in the order of their palette indices.
+const BASIC: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BlackBright,
+    Color::RedBright,
+    Color::GreenBright,
+    Color::YellowBright,
+    Color::BlueBright,
+    Color::MagentaBright,
+    Color::CyanBright,
+    Color::WhiteBright,
+];
+
+/// The output of every macro, in a variety of modes, with its plain text.
+const OUTPUTS: [(&str, &str); 69] = {
+    macro_rules! table {
+        ($($output:expr),* $(,)?) => {
+            [$(($output, sgr_strip!($output))),*]
+        };
+    }
+
+    table![
+        sgr_bold!("bold"),
+        sgr_faint!(* "faint"),
+        sgr_italic!(! "italic"),
+        sgr_uline!("uline"),
+        sgr_uline_double!("uline_double"),
+        sgr_uline_curly!(* "uline_curly"),
+        sgr_uline_dotted!("uline_dotted"),
+        sgr_uline_dashed!(! "uline_dashed"),
+        sgr_blink!("blink"),
+        sgr_blink2!("blink2"),
+        sgr_invert!("invert"),
+        sgr_conceal!(* "conceal"),
+        sgr_strike!("strike"),
+        sgr_super!("super"),
+        sgr_sub!(! "sub"),
+        black!("black"),
+        black_bright!("black_bright"),
+        red!(* "red"),
+        red_bright!("red_bright"),
+        green!("green"),
+        green_bright!(! "green_bright"),
+        yellow!("yellow"),
+        yellow_bright!("yellow_bright"),
+        blue!("blue"),
+        blue_bright!(* "blue_bright"),
+        magenta!("magenta"),
+        magenta_bright!("magenta_bright"),
+        cyan!(! "cyan"),
+        cyan_bright!("cyan_bright"),
+        white!("white"),
+        white_bright!("white_bright"),
+        black_bg!("black_bg"),
+        black_bright_bg!("black_bright_bg"),
+        red_bg!("red_bg"),
+        red_bright_bg!(* "red_bright_bg"),
+        green_bg!("green_bg"),
+        green_bright_bg!("green_bright_bg"),
+        yellow_bg!(! "yellow_bg"),
+        yellow_bright_bg!("yellow_bright_bg"),
+        blue_bg!("blue_bg"),
+        blue_bright_bg!("blue_bright_bg"),
+        magenta_bg!("magenta_bg"),
+        magenta_bright_bg!("magenta_bright_bg"),
+        cyan_bg!("cyan_bg"),
+        cyan_bright_bg!(! "cyan_bright_bg"),
+        white_bg!("white_bg"),
+        white_bright_bg!("white_bright_bg"),
+        color_256!(173; "color_256"),
+        color_256_bg!(64; * "color_256_bg"),
+        color_rgb!(0x420311; "color_rgb"),
+        color_rgb_bg!(steelblue; ! "color_rgb_bg"),
+        uline_color_256!(160; sgr_uline_curly!("uline_color_256")),
+        uline_color_rgb!(red; "uline_color_rgb"),
+        sgr!(bold, italic, fg = 0x112233, bg = 0x445566; "sgr"),
+        sgr!(uline_dashed, red_bright, ul = 0x00FF00; * "sgr"),
+        sgr_bold!("a ", sgr_faint!("b ", red!("c")), " d"),
+        sgr_bold!(* sgr_italic!(! sgr_uline!(! "e"))),
+        red!(concat!("f ", green!("g"), " h")),
+        sgr_italic!("i ", green!(* "j"), " k"),
+        sgr_markup!("<b>l</b> <red>m <blue_bg>n</blue_bg></red> <<"),
+        sgr_markup!(* "<u>o</u> <256_bg=64><invert>p</invert></256_bg>"),
+        hyperlink!("https://docs.rs"; "q"),
+        hyperlink!("https://docs.rs", id = "d"; blue!("r")),
+        sgr_gradient!(red -> blue; "gradient"),
+        sgr_gradient!(red -> lime -> blue, bg, color_256; "s t u"),
+        concat!("v", sgr_reset!(), "<&>"),
+        "\x1B[2K\x1B[?25lw\x1B[>4;2mx\x1B]0;title\x07",
+        "",
+        "plain",
+    ]
+};
+
+
+/// Find the state that a style should produce.
+fn expected(style: &Style) -> StyleState {
+    let mut state = StyleState::default();
+
+    for attr in Attr::ALL.into_iter().filter(|attr| style.has(*attr)) {
+        match attr {
+            Attr::Bold => state.bold = true,
+            Attr::Faint => state.faint = true,
+            Attr::Italic => state.italic = true,
+            Attr::Uline => state.underline = Underline::Single,
+            Attr::UlineDouble => state.underline = Underline::Double,
+            Attr::UlineCurly => state.underline = Underline::Curly,
+            Attr::UlineDotted => state.underline = Underline::Dotted,
+            Attr::UlineDashed => state.underline = Underline::Dashed,
+            Attr::Blink => state.blink = Blink::Slow,
+            Attr::Blink2 => state.blink = Blink::Rapid,
+            Attr::Invert => state.invert = true,
+            Attr::Conceal => state.conceal = true,
+            Attr::Strike => state.strike = true,
+            Attr::Super => state.script = Script::Super,
+            Attr::Sub => state.script = Script::Sub,
+        }
+    }
+
+    state
+}
+
+
+/// Join the text of some runs.
+fn text(runs: &[(String, StyleState)]) -> String {
+    runs.iter().map(|(text, _)| text.as_str()).collect()
+}
+
+
+#[test]
+fn test_parse_attrs() {
+    for attr in Attr::ALL {
+        let style = Style::new().attr(attr);
+        let state = expected(&style);
+
+        for revert in [Revert::One, Revert::All, Revert::None] {
+            let output = style.revert(revert).paint("text").to_string() + "x";
+
+            //  Closing the style restores the default state.
+            let expected = match revert {
+                Revert::None => vec![(String::from("textx"), state)],
+                _ => vec![
+                    (String::from("text"), state),
+                    (String::from("x"), StyleState::default()),
+                ],
+            };
+
+            assert_eq!(parse(&output).unwrap(), expected, "{attr:?}");
+        }
+    }
+
+    //  Bold and faint are closed together.
+    assert_eq!(
+        parse(sgr_bold!("a", sgr_faint!("b"), "c")).unwrap(),
+        [
+            (String::from("a"), expected(&Style::new().attr(Attr::Bold))),
+            (
+                String::from("b"),
+                expected(&Style::new().attr(Attr::Bold).attr(Attr::Faint)),
+            ),
+            (String::from("c"), expected(&Style::new().attr(Attr::Bold))),
+        ],
+    );
+}
+
+
+#[test]
+fn test_parse_colors() {
+    for (i, color) in BASIC.into_iter().enumerate() {
+        for layer in 0..3 {
+            let (style, state) = match layer {
+                0 => (
+                    Style::new().fg(color),
+                    StyleState { fg: Some(color), ..StyleState::default() },
+                ),
+                1 => (
+                    Style::new().bg(color),
+                    StyleState { bg: Some(color), ..StyleState::default() },
+                ),
+                //  Basic underline colors are written as indexed colors.
+                _ => (
+                    Style::new().uline_color(color),
+                    StyleState {
+                        uline_color: Some(Color::Indexed(i as u8)),
+                        ..StyleState::default()
+                    },
+                ),
+            };
+
+            let output = style.paint("text").to_string() + "x";
+
+            assert_eq!(
+                parse(&output).unwrap(),
+                [
+                    (String::from("text"), state),
+                    (String::from("x"), StyleState::default()),
+                ],
+                "{color:?}",
+            );
+        }
+    }
+
+    let fg = |color| StyleState { fg: Some(color), ..StyleState::default() };
+
+    assert_eq!(
+        parse(color_256!(173; "a")).unwrap(),
+        [(String::from("a"), fg(Color::Indexed(173)))],
+    );
+    assert_eq!(
+        parse(color_rgb!((255, 127, 63); "a")).unwrap(),
+        [(String::from("a"), fg(Color::Rgb(255, 127, 63)))],
+    );
+    assert_eq!(
+        parse("\x1B[38:2::1:2:3ma\x1B[38:5:9mb").unwrap(),
+        [
+            (String::from("a"), fg(Color::Rgb(1, 2, 3))),
+            (String::from("b"), fg(Color::Indexed(9))),
+        ],
+    );
+}
+
+
+#[test]
+fn test_parse_malformed() {
+    let error = |offset, kind| Err(ParseError { offset, kind });
+    let param = |param: &str| ErrorKind::Parameter(String::from(param));
+
+    assert_eq!(parse("a\x1B[1;99999mb"), error(1, param("99999")));
+    assert_eq!(parse("\x1B[38;5m"), error(0, param("38;5")));
+    assert_eq!(parse("\x1B[1m\x1B[48;2;1;2m"), error(4, param("48;2;1;2")));
+    assert_eq!(parse("\x1B[58;5;256m"), error(0, param("58;5;256")));
+    assert_eq!(parse("\x1B[4:9m"), error(0, param("4:9")));
+    assert_eq!(parse("\x1B"), error(0, ErrorKind::Incomplete));
+    assert_eq!(parse("ab\x1B["), error(2, ErrorKind::Incomplete));
+    assert_eq!(parse("\x1B(B"), error(0, ErrorKind::Incomplete));
+    assert_eq!(parse("\x1B]8;;https://a.b"), error(0, ErrorKind::Incomplete));
+
+    assert_eq!(
+        parse("\x1B[38;5m").unwrap_err().to_string(),
+        "invalid SGR parameter `38;5` at byte 0",
+    );
+
+    //  Numbers that no macro uses are not errors.
+    assert_eq!(
+        parse("\x1B[10;26;50ma").unwrap(),
+        [(String::from("a"), StyleState::default())],
+    );
+
+    //  Every output of a macro is cut short in each control sequence.
+    for (output, _) in OUTPUTS {
+        let mut next = 0;
+
+        for (start, _) in output.match_indices('\x1B') {
+            //  The escape character of a String Terminator is skipped.
+            if start < next {
+                continue;
+            }
+
+            //  Find the position of the last byte of the sequence.
+            let seq = &output[start..];
+            let len = match seq.strip_prefix("\x1B[") {
+                Some(csi) => 2 + csi.find(|c| c >= '\x40').unwrap(),
+                None => seq.find(['\x07', '\\']).unwrap(),
+            };
+            next = start + len + 1;
+
+            for end in start + 1..=start + len {
+                assert_eq!(
+                    parse(&output[..end]),
+                    error(start, ErrorKind::Incomplete),
+                    "{output:?}",
+                );
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_parse_outputs() {
+    for (output, plain) in OUTPUTS {
+        let runs = parse(output).unwrap();
+        assert_eq!(text(&runs), plain, "{output:?}");
+    }
+}
+
+
+/// A random selection of macro outputs.
+#[derive(Clone, Debug)]
+struct Sample(Vec<usize>);
+
+impl Arbitrary for Sample {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let len = usize::arbitrary(g) % 8;
+        Self((0..len).map(|_| usize::arbitrary(g) % OUTPUTS.len()).collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.0.shrink().map(Self))
+    }
+}
+
+
+/// A random combination of attributes and colors.
+#[derive(Clone, Debug)]
+struct Styled(Style, Option<Color>);
+
+impl Arbitrary for Styled {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let mut style = Style::new();
+
+        for attr in Attr::ALL {
+            if bool::arbitrary(g) {
+                style = style.attr(attr);
+            }
+        }
+
+        let color = match u8::arbitrary(g) % 4 {
+            0 => None,
+            1 => Some(*g.choose(&BASIC).unwrap()),
+            2 => Some(Color::Indexed(u8::arbitrary(g))),
+            _ => Some(Color::Rgb(
+                u8::arbitrary(g),
+                u8::arbitrary(g),
+                u8::arbitrary(g),
+            )),
+        };
+
+        Self(style, color)
+    }
+}
+
+
+quickcheck! {
+    /// Any sequence of macro outputs should parse, with the text of all of
+    ///     them, and without runs that could be joined.
+    fn prop_parse_outputs(sample: Sample) -> bool {
+        let input: String = sample.0.iter().map(|&i| OUTPUTS[i].0).collect();
+        let plain: String = sample.0.iter().map(|&i| OUTPUTS[i].1).collect();
+        let runs = parse(&input).unwrap();
+
+        text(&runs) == plain
+            && runs.iter().all(|(text, _)| !text.is_empty())
+            && runs.windows(2).all(|pair| pair[0].1 != pair[1].1)
+            && parse(&(input + sgr_reset!() + "x")).unwrap().last()
+                .is_some_and(|(text, state)| {
+                    text.ends_with('x') && state.is_empty()
+                })
+    }
+
+    /// Any style should parse to the state that it describes.
+    fn prop_parse_style(styled: Styled) -> bool {
+        let Styled(style, color) = styled;
+        let mut state = expected(&style);
+        let style = match color {
+            Some(color) => {
+                state.fg = Some(color);
+                style.fg(color)
+            }
+            None => style,
+        };
+
+        let output = style.paint("text").to_string() + "x";
+        let runs = parse(&output).unwrap();
+
+        //  Without any style, the text is one run.
+        if state.is_empty() {
+            return runs == [(String::from("textx"), state)];
+        }
+
+        runs.first() == Some(&(String::from("text"), state))
+            && runs.last()
+                == Some(&(String::from("x"), StyleState::default()))
+    }
+
+    /// Any text should either parse or be an error, without panicking, and
+    ///     only lose its control sequences.
+    fn prop_parse_noise(noise: Vec<u8>) -> bool {
+        const ALPHABET: &[u8] = b"\x1B\x07[];:\\m0123456789?x ";
+        let input: String = noise.iter()
+            .map(|&b| char::from(ALPHABET[usize::from(b) % ALPHABET.len()]))
+            .collect();
+
+        match parse(&input) {
+            Ok(runs) => text(&runs).len() <= input.len(),
+            Err(error) => input[error.offset..].starts_with('\x1B'),
+        }
+    }
+}
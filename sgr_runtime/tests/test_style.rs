@@ -0,0 +1,270 @@
+use quickcheck::{quickcheck, Arbitrary, Gen};
+use sgr_macros::*;
+use sgr_runtime::{set_override, Attr, Color, Revert, Stream, Style};
+
+
+/// Every attribute, with the output of its macro in each reversion mode.
+const ATTRS: [(Attr, [&str; 3]); 15] = {
+    macro_rules! table {
+        ($($attr:ident => $mac:ident),* $(,)?) => {
+            [$((
+                Attr::$attr,
+                [$mac!("text"), $mac!(* "text"), $mac!(! "text")],
+            )),*]
+        };
+    }
+
+    table![
+        Bold => sgr_bold,
+        Faint => sgr_faint,
+        Italic => sgr_italic,
+        Uline => sgr_uline,
+        UlineDouble => sgr_uline_double,
+        UlineCurly => sgr_uline_curly,
+        UlineDotted => sgr_uline_dotted,
+        UlineDashed => sgr_uline_dashed,
+        Blink => sgr_blink,
+        Blink2 => sgr_blink2,
+        Invert => sgr_invert,
+        Conceal => sgr_conceal,
+        Strike => sgr_strike,
+        Super => sgr_super,
+        Sub => sgr_sub,
+    ]
+};
+
+/// Foreground colors, with the output of the equivalent macro.
+const FG: [(Color, &str); 20] = [
+    (Color::Black, black!("text")),
+    (Color::Red, red!("text")),
+    (Color::Green, green!("text")),
+    (Color::Yellow, yellow!("text")),
+    (Color::Blue, blue!("text")),
+    (Color::Magenta, magenta!("text")),
+    (Color::Cyan, cyan!("text")),
+    (Color::White, white!("text")),
+    (Color::BlackBright, black_bright!("text")),
+    (Color::RedBright, red_bright!("text")),
+    (Color::GreenBright, green_bright!("text")),
+    (Color::YellowBright, yellow_bright!("text")),
+    (Color::BlueBright, blue_bright!("text")),
+    (Color::MagentaBright, magenta_bright!("text")),
+    (Color::CyanBright, cyan_bright!("text")),
+    (Color::WhiteBright, white_bright!("text")),
+    (Color::Indexed(0), color_256!(0; "text")),
+    (Color::Indexed(173), color_256!(173; "text")),
+    (Color::Rgb(0, 0, 0), color_rgb!(0x000000; "text")),
+    (Color::Rgb(255, 127, 63), color_rgb!((255, 127, 63); "text")),
+];
+
+/// Background colors, with the output of the equivalent macro.
+const BG: [(Color, &str); 20] = [
+    (Color::Black, black_bg!("text")),
+    (Color::Red, red_bg!("text")),
+    (Color::Green, green_bg!("text")),
+    (Color::Yellow, yellow_bg!("text")),
+    (Color::Blue, blue_bg!("text")),
+    (Color::Magenta, magenta_bg!("text")),
+    (Color::Cyan, cyan_bg!("text")),
+    (Color::White, white_bg!("text")),
+    (Color::BlackBright, black_bright_bg!("text")),
+    (Color::RedBright, red_bright_bg!("text")),
+    (Color::GreenBright, green_bright_bg!("text")),
+    (Color::YellowBright, yellow_bright_bg!("text")),
+    (Color::BlueBright, blue_bright_bg!("text")),
+    (Color::MagentaBright, magenta_bright_bg!("text")),
+    (Color::CyanBright, cyan_bright_bg!("text")),
+    (Color::WhiteBright, white_bright_bg!("text")),
+    (Color::Indexed(255), color_256_bg!(255; "text")),
+    (Color::Indexed(64), color_256_bg!(64; "text")),
+    (Color::Rgb(255, 255, 255), color_rgb_bg!(0xFFFFFF; "text")),
+    (Color::Rgb(51, 85, 85), color_rgb_bg!("#335555"; "text")),
+];
+
+/// Underline colors, with the output of the equivalent macro.
+const UL: [(Color, &str); 5] = [
+    (Color::Red, uline_color_256!(1; "text")),
+    (Color::WhiteBright, uline_color_256!(15; "text")),
+    (Color::Indexed(160), uline_color_256!(160; "text")),
+    (Color::Rgb(255, 0, 0), uline_color_rgb!(red; "text")),
+    (Color::Rgb(1, 2, 3), uline_color_rgb!(0x010203; "text")),
+];
+
+
+/// Split the output of a macro into its opening and closing parameters.
+fn params(output: &str) -> (&str, &str) {
+    let output = output.strip_prefix("\x1B[").unwrap();
+    let (opening, output) = output.split_once("mtext\x1B[").unwrap();
+    let closing = output.strip_suffix('m').unwrap();
+
+    (opening, closing)
+}
+
+
+#[test]
+fn test_style_attrs() {
+    for (attr, [one, all, none]) in ATTRS {
+        let style = Style::new().attr(attr);
+
+        assert_eq!(style.paint("text").to_string(), one, "{attr:?}");
+        assert_eq!(
+            style.revert(Revert::All).paint("text").to_string(),
+            all,
+            "{attr:?}",
+        );
+        assert_eq!(
+            style.revert(Revert::None).paint("text").to_string(),
+            none,
+            "{attr:?}",
+        );
+    }
+}
+
+
+#[test]
+fn test_style_colors() {
+    for (color, output) in FG {
+        let style = Style::new().fg(color);
+        assert_eq!(style.paint("text").to_string(), output, "{color:?}");
+    }
+
+    for (color, output) in BG {
+        let style = Style::new().bg(color);
+        assert_eq!(style.paint("text").to_string(), output, "{color:?}");
+    }
+
+    for (color, output) in UL {
+        let style = Style::new().uline_color(color);
+        assert_eq!(style.paint("text").to_string(), output, "{color:?}");
+    }
+}
+
+
+#[test]
+fn test_style_composite() {
+    let style = Style::new()
+        .attr(Attr::Italic)
+        .attr(Attr::Bold)
+        .fg(Color::Red)
+        .bg(Color::Rgb(0x22, 0x33, 0x44));
+
+    assert_eq!(
+        style.paint("text").to_string(),
+        sgr!(bold, italic, red, bg = 0x223344; "text"),
+    );
+
+    let style = Style::new()
+        .attr(Attr::Faint)
+        .attr(Attr::Bold)
+        .attr(Attr::UlineCurly)
+        .uline_color(Color::Indexed(9));
+
+    assert_eq!(
+        style.paint(42).to_string(),
+        sgr!(bold, faint, uline_curly, uline_color_256 = 9; @ "{}", 42),
+    );
+
+    //  An empty style writes only its value.
+    assert_eq!(Style::new().paint("text").to_string(), "text");
+}
+
+
+#[test]
+fn test_style_gated() {
+    let style = Style::new().attr(Attr::Bold).gate(Stream::Stdout);
+
+    set_override(Some(true));
+    assert_eq!(style.paint("text").to_string(), sgr_bold!("text"));
+
+    set_override(Some(false));
+    assert_eq!(style.paint("text").to_string(), "text");
+
+    //  An ungated style is unaffected.
+    let style = Style::new().attr(Attr::Bold);
+    assert_eq!(style.paint("text").to_string(), sgr_bold!("text"));
+
+    set_override(None);
+}
+
+
+/// A random selection of entries from the tables above.
+#[derive(Clone, Debug)]
+struct Sample {
+    attrs: Vec<usize>,
+    fg: Option<usize>,
+    bg: Option<usize>,
+    ul: Option<usize>,
+    revert: Revert,
+}
+
+impl Arbitrary for Sample {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let pick = |g: &mut Gen, len: usize| {
+            bool::arbitrary(g).then(|| usize::arbitrary(g) % len)
+        };
+
+        Self {
+            attrs: (0..ATTRS.len()).filter(|_| bool::arbitrary(g)).collect(),
+            fg: pick(g, FG.len()),
+            bg: pick(g, BG.len()),
+            ul: pick(g, UL.len()),
+            revert: *g.choose(&[Revert::One, Revert::All, Revert::None])
+                .unwrap(),
+        }
+    }
+}
+
+
+quickcheck! {
+    /// Any combination of attributes and colors should produce the same
+    ///     output as a composite of their macros.
+    fn prop_style_composite(sample: Sample) -> bool {
+        let mut style = Style::new().revert(sample.revert);
+        let mut parts: Vec<&str> = Vec::new();
+
+        for &i in &sample.attrs {
+            style = style.attr(ATTRS[i].0);
+            parts.push(ATTRS[i].1[0]);
+        }
+
+        if let Some(i) = sample.fg {
+            style = style.fg(FG[i].0);
+            parts.push(FG[i].1);
+        }
+
+        if let Some(i) = sample.bg {
+            style = style.bg(BG[i].0);
+            parts.push(BG[i].1);
+        }
+
+        if let Some(i) = sample.ul {
+            style = style.uline_color(UL[i].0);
+            parts.push(UL[i].1);
+        }
+
+        let mut opening: Vec<&str> = Vec::new();
+        let mut closing: Vec<&str> = Vec::new();
+
+        for (open, close) in parts.into_iter().map(params) {
+            opening.push(open);
+
+            if !closing.contains(&close) {
+                closing.push(close);
+            }
+        }
+
+        let expected = if opening.is_empty() {
+            String::from("text")
+        } else {
+            let end = match sample.revert {
+                Revert::One => format!("\x1B[{}m", closing.join(";")),
+                Revert::All => String::from("\x1B[m"),
+                Revert::None => String::new(),
+            };
+
+            format!("\x1B[{}mtext{end}", opening.join(";"))
+        };
+
+        style.paint("text").to_string() == expected
+    }
+}
@@ -7,18 +7,20 @@
 //! Note that not all terminal emulators will support all of the effects
 //!     provided in this crate. These macros exist solely to apply the relevant
 //!     control sequences, and have no capability to discern whether the
-//!     sequences will be meaningful.
+//!     sequences will be meaningful. However, they can be made to omit the
+//!     sequences when color is disabled or output is not a terminal; see
+//!     [Runtime Gating](#runtime-gating).
 //!
 //! [ANSI escape codes]: https://en.wikipedia.org/wiki/ANSI_escape_code#SGR
 //!
 //! ## Modes
 //!
-//! There are three "output modes" to every macro in this crate: Literal,
-//!     Format, and String. These determine the output type of the macro, and
-//!     whether it can be called in `const` contexts. Additionally, there are
-//!     three "reversion modes": Single, Total, and None. These determine what
-//!     is to be done at the end of a macro call --- the formatting state that
-//!     should be *reverted*.
+//! There are six "output modes" to every macro in this crate: Literal,
+//!     Format, String, Write, Print, and Lazy. These determine the output type
+//!     of the macro, and whether it can be called in `const` contexts.
+//!     Additionally, there are three "reversion modes": Single, Total, and
+//!     None. These determine what is to be done at the end of a macro call ---
+//!     the formatting state that should be *reverted*.
 //!
 //! ### Output Modes
 //!
@@ -27,6 +29,10 @@
 //!     a Literal Mode macro invocation is a string literal, suitable for the
 //!     value of a `const`, or as input to compile-time macros (such as
 //!     [`concat!`] or another SGR macro).
+//!
+//! Because the whole output is known, consecutive control sequences in it are
+//!     merged into one, and any parameter that would have no visible effect is
+//!     removed. The result always renders the same as the unoptimized output.
 //! ```
 //! use sgr_macros::*;
 //!
@@ -34,12 +40,28 @@
 //! assert_eq!(green, "\x1B[32mGreen Text\x1B[39m");
 //!
 //! let bold: &'static str = sgr_bold!(green!("Bold Green Text"));
-//! assert_eq!(bold, "\x1B[1m\x1B[32mBold Green Text\x1B[39m\x1B[22m");
+//! assert_eq!(bold, "\x1B[1;32mBold Green Text\x1B[39;22m");
 //!
 //! let concat: &'static str = concat!(sgr_bold!("Bold Text"), ", Normal Text");
 //! assert_eq!(concat, "\x1B[1mBold Text\x1B[22m, Normal Text");
 //! ```
 //!
+//! A template literal may also be used in this mode, if every argument to it
+//!     is a literal, or a Literal Mode invocation. The template is filled in
+//!     at compile-time, and may have positional and named placeholders, with a
+//!     fill, alignment, and width. The first literal is only a template if it
+//!     has a placeholder and is followed by arguments; otherwise, it is
+//!     concatenated like the rest.
+//! ```
+//! use sgr_macros::*;
+//!
+//! let items: &'static str = green!("{} items in {cart}", "3", cart = "cart");
+//! assert_eq!(items, "\x1B[32m3 items in cart\x1B[39m");
+//!
+//! let count: &'static str = concat!("[", sgr_bold!("{:>3}", red!("7")), "]");
+//! assert_eq!(count, "[\x1B[1m  \x1B[31m7\x1B[39;22m]");
+//! ```
+//!
 //! The second mode is Format Mode. An invocation in this mode will resolve to a
 //!     call to [`format_args!`]. This will return [`Arguments`] suitable as
 //!     input to formatting macros such as [`format!`], [`println!`], and
@@ -81,15 +103,93 @@
 //! }
 //!
 //! let text: String = status(true, "Success.");
-//! assert_eq!(text, "\x1B[94mSuccess.\x1B[99m");
+//! assert_eq!(text, "\x1B[94mSuccess.\x1B[39m");
 //!
 //! let text: String = status(false, "System is on fire.");
 //! assert_eq!(text, "\x1B[31mERROR: System is on fire.\x1B[39m");
 //! ```
 //!
+//! The fourth mode is Write Mode. An invocation in this mode will resolve to a
+//!     call to [`write!`] on a target, returning its result: [`fmt::Result`]
+//!     for a [`Formatter`] or any [`fmt::Write`], or [`io::Result`] for any
+//!     [`io::Write`]. This mode is enabled with a `>` sigil, followed by the
+//!     target and a semicolon, and it may also be provided a template literal.
+//!
+//! [`fmt::Result`]: std::fmt::Result
+//! [`Formatter`]: std::fmt::Formatter
+//! [`fmt::Write`]: std::fmt::Write
+//! [`io::Result`]: std::io::Result
+//! [`io::Write`]: std::io::Write
+//! ```
+//! use std::{fmt::{self, Display}, io::Write};
+//! use sgr_macros::*;
+//!
+//! struct Error(&'static str);
+//!
+//! impl Display for Error {
+//!     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         red!(> f; "ERROR: {}", self.0)
+//!     }
+//! }
+//!
+//! let text: String = Error("System is on fire.").to_string();
+//! assert_eq!(text, "\x1B[31mERROR: System is on fire.\x1B[39m");
+//!
+//! let mut bytes: Vec<u8> = Vec::new();
+//! bytes.write_all(b"Status: ").unwrap();
+//! green!(> bytes; "{}", "OK").unwrap();
+//! assert_eq!(bytes, b"Status: \x1B[32mOK\x1B[39m");
+//! ```
+//!
+//! The fifth mode is Print Mode. An invocation in this mode will resolve to a
+//!     call to [`println!`] with a `>>` sigil, or to [`eprintln!`] with `>>!`.
+//!     These may also be written as in a shell, where `1` is standard output
+//!     and `2` is standard error: `1>>` is the same as `>>`, and `2>>` the
+//!     same as `>>!`. Without a newline, `1>` resolves to [`print!`], and `2>`
+//!     to [`eprint!`]. Since the `!` of `>>!` is not a revert sigil, a line is
+//!     printed without reverting by `1>>!` or `>>!!`. This mode may also be
+//!     provided a template literal. When [gated](#runtime-gating), the color
+//!     support of the stream that is printed to is checked.
+//! ```
+//! use sgr_macros::*;
+//!
+//! fn report(result: Result<&str, &str>) {
+//!     match result {
+//!         Ok(msg) => green!(>> "{}", msg),
+//!         Err(e) => red!(>>!? "error: {}", e),
+//!     }
+//! }
+//!
+//! report(Ok("Done."));
+//! report(Err("System is on fire."));
+//! ```
+//!
+//! The sixth mode is Lazy Mode. An [`Arguments`] cannot outlive the temporaries
+//!     that it borrows, and a [`String`] must be allocated. An invocation in
+//!     this mode instead resolves to a value that implements [`Display`] and
+//!     [`Debug`], and is only formatted when it is displayed. With a `&`
+//!     sigil, its arguments are borrowed, and with `& move`, they are moved
+//!     into it, so that it can be returned from a function or stored. Either
+//!     way, they are evaluated each time it is displayed. This mode requires
+//!     the [`sgr_runtime`] crate, and may also be provided a template literal.
+//!
+//! [`Display`]: std::fmt::Display
+//! [`Debug`]: std::fmt::Debug
+//! ```
+//! use std::fmt::Display;
+//! use sgr_macros::*;
+//!
+//! fn temperature(degrees: i32) -> impl Display {
+//!     cyan!(& move "{}°C", degrees)
+//! }
+//!
+//! let text: String = format!("It is {} outside.", temperature(-5));
+//! assert_eq!(text, "It is \x1B[36m-5°C\x1B[39m outside.");
+//! ```
+//!
 //! [`const_format::formatcp!`]: https://docs.rs/const_format/0.2.26/const_format/macro.formatcp.html
 //!
-//! If the "const" Cargo Feature is enabled, a fourth mode is available: Const
+//! If the "const" Cargo Feature is enabled, a seventh mode is available: Const
 //!     Format Mode. An invocation in this mode will resolve to a call to
 //!     [`const_format::formatcp!`], returning a static string slice. This
 //!     output is NOT a string literal, however, and is not suitable as input to
@@ -122,15 +222,30 @@
 //!     control sequence to set normal intensity. Similarly, all coloring macros
 //!     will set the default text color when they end.
 //!
-//! Some styles share a revert sequence, meaning that they cannot be safely
-//!     nested; The end of the inner style will also revert the outer style.
-//!     This is true of the following groups of macros:
+//! Some styles share a revert sequence, meaning that the end of an inner style
+//!     would also revert the outer style. This is true of the following groups
+//!     of macros:
 //! - [`sgr_bold!`] and [`sgr_faint!`]
 //! - [`sgr_blink!`] and [`sgr_blink2!`]
 //! - [`sgr_super!`] and [`sgr_sub!`]
+//! - [`sgr_uline!`] and its variants, such as [`sgr_uline_curly!`]
 //! - All color macros (basic, indexed, and RGB) that do not end in `*_bg`.
 //! - All color macros (basic, indexed, and RGB) that **do** end in `*_bg`.
 //!
+//! To allow these styles to be nested safely, every macro detects invocations
+//!     of this crate's macros within its own input, including within calls to
+//!     [`concat!`] and within the arguments of a template. Wherever a nested
+//!     invocation would revert the outer style, the outer style is applied
+//!     again immediately afterwards.
+//! ```
+//! use sgr_macros::*;
+//!
+//! assert_eq!(
+//!     red!(concat!("a ", green!("b"), " c")),
+//!     "\x1B[31ma \x1B[32mb\x1B[31m c\x1B[39m",
+//! );
+//! ```
+//!
 //! To control the behavior of revert sequences, there are two more sigils: `!`
 //!     to prevent reverting *any* formatting, and `*` to revert *all*
 //!     formatting. Like the Output Mode sigils, these are placed at the
@@ -141,30 +256,29 @@
 //!
 //! assert_eq!(
 //!     //  Here, several layered formatting codes are applied, and then cleared
-//!     //      individually. This uses a considerable number of bytes compared
-//!     //      to how it might be done manually.
+//!     //      individually. Even when merged, this uses more bytes than would
+//!     //      be needed to clear everything at once.
 //!     sgr_bold!(sgr_italic!(sgr_uline!("WHAM"))),
-//!     "\x1B[1m\x1B[3m\x1B[4mWHAM\x1B[24m\x1B[23m\x1B[22m",
+//!     "\x1B[1;3;4mWHAM\x1B[24;23;22m",
 //! );
 //! assert_eq!(
 //!     //  One way to address this is to specify that `sgr_uline!` and
 //!     //      `sgr_italic!` should NOT revert, and that `sgr_bold!` should
 //!     //      revert ALL formatting.
 //!     sgr_bold!(* sgr_italic!(! sgr_uline!(! "WHAM"))),
-//!     "\x1B[1m\x1B[3m\x1B[4mWHAM\x1B[m",
+//!     "\x1B[1;3;4mWHAM\x1B[m",
 //! );
 //!
 //! assert_eq!(
-//!     //  Here, the color is reset to default twice: Once at the end of Blue,
-//!     //      and again at the end of (Not) Red.
-//!     red!(@ "Red, {}, (Not) Red", blue!("Blue")),
-//!     "\x1B[31mRed, \x1B[34mBlue\x1B[39m, (Not) Red\x1B[39m",
+//!     //  Here, the color is reset to default at the end of Blue, and then Red
+//!     //      is applied again, before finally being reset at the end.
+//!     red!(@ "Red, {}, Red Again", blue!("Blue")),
+//!     "\x1B[31mRed, \x1B[34mBlue\x1B[39m\x1B[31m, Red Again\x1B[39m",
 //! );
 //! assert_eq!(
 //!     //  Here, the color is not reset at the end of Blue, resulting in its
-//!     //      blue formatting spilling out to the end of the string. This sort
-//!     //      of conflict cannot be solved at compilation while using multiple
-//!     //      macros, so it is best to avoid nesting colors whenever possible.
+//!     //      blue formatting spilling out to the end of the string. Since
+//!     //      Blue does not revert anything, Red is not applied again.
 //!     red!(@ "Red, {}, Still Blue", blue!(! "Blue")),
 //!     "\x1B[31mRed, \x1B[34mBlue, Still Blue\x1B[39m",
 //! );
@@ -196,6 +310,44 @@
 //! );
 //! ```
 //!
+//! ### Runtime Gating
+//!
+//! Control sequences are normally written unconditionally, which leaves them
+//!     behind as garbage when output is redirected to a file. With a `?` sigil,
+//!     placed after any output sigil and before any revert sigil (e.g. `@?` or
+//!     `%?*`), an invocation instead checks at runtime whether color is
+//!     enabled for standard output, or for standard error if it prints there.
+//!     This is decided by the [`sgr_runtime`] crate, which must be a
+//!     dependency of the calling crate, and which honors `NO_COLOR`,
+//!     `CLICOLOR`, `CLICOLOR_FORCE`, `TERM=dumb`, and whether the stream is a
+//!     terminal.
+//!
+//! In Format, String, Write, and Print Modes, the arguments are evaluated only
+//!     once, and nested invocations of this crate's macros within them are
+//!     gated as well. In Lazy Mode, color is checked each time the value is
+//!     displayed. In Literal Mode, there is nothing to check at runtime, so
+//!     the output is instead a pair of literals: colored and plain. Const
+//!     Format Mode cannot be gated.
+//!
+//! [`sgr_runtime`]: https://docs.rs/sgr_runtime
+//! ```
+//! use sgr_macros::*;
+//! use sgr_runtime::Stream;
+//!
+//! const WARNING: (&str, &str) = yellow!(? "Warning:");
+//! assert_eq!(WARNING, ("\x1B[33mWarning:\x1B[39m", "Warning:"));
+//!
+//! sgr_runtime::set_override(Some(false));
+//! assert_eq!(sgr_runtime::select(WARNING, Stream::Stdout), "Warning:");
+//! assert_eq!(red!(@? "Error: {}", sgr_bold!("failed")), "Error: failed");
+//!
+//! sgr_runtime::set_override(Some(true));
+//! assert_eq!(
+//!     red!(@? "Error: {}", sgr_bold!("failed")),
+//!     "\x1B[31mError: \x1B[1mfailed\x1B[22m\x1B[39m",
+//! );
+//! ```
+//!
 //! ## Macros
 //!
 //! ### Basic Color
@@ -219,11 +371,11 @@
 //! );
 //! assert_eq!(
 //!     sgr_macros::cyan_bright!("Bright Cyan Text"),
-//!     "\x1B[96mBright Cyan Text\x1B[99m",
+//!     "\x1B[96mBright Cyan Text\x1B[39m",
 //! );
 //! assert_eq!(
 //!     sgr_macros::cyan_bright_bg!("Text on Bright Cyan"),
-//!     "\x1B[106mText on Bright Cyan\x1B[109m",
+//!     "\x1B[106mText on Bright Cyan\x1B[49m",
 //! );
 //! ```
 //!
@@ -273,11 +425,162 @@
 //! For more information on the RGB color specification, see the documentation
 //!     on the [`color_rgb!`] macro.
 //!
+//! For terminals without support for 24-bit color, a `color_256` or `color_16`
+//!     option after the color replaces it with the perceptually nearest color
+//!     of the xterm 256-color palette, or of the 16 basic colors,
+//!     respectively. This is done at compile-time, and emits the `38;5;n` form
+//!     for 256 colors, or `30`-`37` and `90`-`97` for 16 colors (`40`-`47` and
+//!     `100`-`107` for backgrounds). Only the invocation with the option is
+//!     affected.
+//! ```
+//! assert_eq!(
+//!     sgr_macros::color_rgb!(0xFF8700, color_256; "Orange Text"),
+//!     "\x1B[38;5;208mOrange Text\x1B[39m",
+//! );
+//! assert_eq!(
+//!     sgr_macros::color_rgb_bg!(0xC0C0C0, color_16; "Text on Silver"),
+//!     "\x1B[47mText on Silver\x1B[49m",
+//! );
+//! ```
+//!
+//! The [`sgr_gradient!`] macro colors each grapheme cluster of a string
+//!     literal separately, with colors mixed evenly between two or more stops.
+//! ```
+//! assert_eq!(
+//!     sgr_macros::sgr_gradient!(red -> blue; "ab"),
+//!     "\x1B[38;2;255;0;0ma\x1B[38;2;0;0;255mb\x1B[39m",
+//! );
+//! ```
+//!
 //! ### Style
 //!
-//! Eleven macros are provided for various "styles" of text. These typically do
+//! Fifteen macros are provided for various "styles" of text. These typically do
 //!     not alter text color, but some aspects, such as text intensity, may be
 //!     implemented by a terminal as changing color brightness or vividness.
+//!
+//! Besides [`sgr_uline!`], four macros apply extended underline styles, which
+//!     are supported by some modern terminals: [`sgr_uline_double!`],
+//!     [`sgr_uline_curly!`], [`sgr_uline_dotted!`], and [`sgr_uline_dashed!`].
+//!     The color of an underline may also be set independently of the text,
+//!     with [`uline_color_256!`] and [`uline_color_rgb!`].
+//! ```
+//! use sgr_macros::*;
+//!
+//! assert_eq!(
+//!     uline_color_rgb!(0xFF0000; sgr_uline_curly!("Misspelled")),
+//!     "\x1B[58;2;255;0;0m\x1B[4:3mMisspelled\x1B[24;59m",
+//! );
+//! ```
+//!
+//! ### Composite
+//!
+//! The [`sgr!`] macro applies any number of the above colors and styles at
+//!     once, using a single control sequence to open them and another single
+//!     control sequence to revert them.
+//! ```
+//! assert_eq!(
+//!     sgr_macros::sgr!(bold, red, bg = 0x223344; "Bold Red on Blue-Grey"),
+//!     "\x1B[1;31;48;2;34;51;68mBold Red on Blue-Grey\x1B[22;39;49m",
+//! );
+//! ```
+//!
+//! A composite style that is used often may be given a name with
+//!     [`define_style!`], which defines a new macro for it.
+//! ```
+//! sgr_macros::define_style!(warning = bold + yellow + bg(0x202020));
+//!
+//! assert_eq!(
+//!     warning!(@ "{} warnings", 3),
+//!     "\x1B[1;33;48;2;32;32;32m3 warnings\x1B[22;39;49m",
+//! );
+//! ```
+//!
+//! ### Markup
+//!
+//! The [`sgr_markup!`] macro applies colors and styles to a template using
+//!     inline tags, such as `<b>` or `<red>`, which are validated and replaced
+//!     with control sequences at compile-time.
+//! ```
+//! assert_eq!(
+//!     sgr_macros::sgr_markup!("<b>bold</b> and <red>red</red>"),
+//!     "\x1B[1mbold\x1B[22m and \x1B[31mred\x1B[39m",
+//! );
+//! ```
+//!
+//! ### Hyperlinks
+//!
+//! The [`hyperlink!`] macro makes text into a clickable link, in terminals that
+//!     support the OSC 8 control sequence. The URL comes first, followed by a
+//!     semicolon, and the text may contain any of the other macros.
+//! ```
+//! use sgr_macros::*;
+//!
+//! assert_eq!(
+//!     red!(concat!("See ", hyperlink!("https://docs.rs"; blue!("Docs")))),
+//!     concat!(
+//!         "\x1B[31mSee \x1B]8;;https://docs.rs\x1B\\",
+//!         "\x1B[34mDocs\x1B[39m\x1B]8;;\x1B\\",
+//!     ),
+//! );
+//! ```
+//!
+//! ### Cursor Control
+//!
+//! Control sequences other than SGR, which move the cursor, erase text, or
+//!     change how the screen scrolls, are made by macros such as
+//!     [`cursor_up!`], [`erase_line!`], and [`scroll_region!`]. Their
+//!     parameters are integer literals, which are checked at compile-time. A
+//!     parameter known only at runtime requires an output mode sigil, after
+//!     which a template may be given for the parameters, as with the other
+//!     macros. Gating and reversion sigils are not accepted.
+//! ```
+//! use sgr_macros::*;
+//!
+//! assert_eq!(concat!(erase_line!(2), "\r"), "\x1B[2K\r");
+//! assert_eq!(cursor_position!(5, 10), "\x1B[5;10H");
+//!
+//! let lines = 3;
+//! assert_eq!(cursor_up!(@ lines), "\x1B[3A");
+//! assert_eq!(cursor_up!(% "{}", lines).to_string(), "\x1B[3A");
+//! ```
+//!
+//! ### Width
+//!
+//! Because control sequences take no space in a terminal, the length of a
+//!     string does not match its width once it is colored. The [`sgr_strip!`]
+//!     macro removes every control sequence from the output of the other
+//!     macros at compile-time, and [`sgr_width!`] finds the number of columns
+//!     that it occupies, accounting for wide and zero-width characters.
+//! ```
+//! use sgr_macros::*;
+//!
+//! assert_eq!(sgr_strip!(red!(concat!("a ", green!("b")))), "a b");
+//! assert_eq!(sgr_width!(sgr_bold!("日本")), 4);
+//! ```
+//!
+//! For the same reason, a width given in a template, such as `{:>8}`, is
+//!     applied to the visible text of an argument that is a string literal,
+//!     [`concat!`], or an invocation of a macro from this crate. The fill and
+//!     alignment are kept, but other format specs, such as `{:08}`, are left to
+//!     the standard macros. If the text is not known until runtime, padding it
+//!     requires the `sgr_runtime` crate, and is not available in Const Mode.
+//! ```
+//! use sgr_macros::*;
+//!
+//! assert_eq!(
+//!     sgr_bold!(@ "[{:>4}]", red!("日")),
+//!     "\x1B[1m[  \x1B[31m日\x1B[39m]\x1B[22m",
+//! );
+//! ```
+//!
+//! ### Themes
+//!
+//! With the "theme" Cargo Feature enabled, the `sgr_theme!` macro reads a TOML
+//!     file at compile-time, which gives a name to each style used by a
+//!     program, such as `error` or `path`. Each name becomes a macro, such as
+//!     `theme_error!`, that accepts all the same mode sigils as [`sgr!`]. Every
+//!     entry of the file is validated, and an invalid entry is a compile error
+//!     that names its line and key.
 
 mod sgr;
 
@@ -300,7 +603,7 @@ use sgr::*;
 ///     macro syntax.
 #[proc_macro]
 pub fn color_256(stream: TokenStream) -> TokenStream {
-    let sgr_256 = syn::parse_macro_input!(stream as Sgr256<false>);
+    let sgr_256 = syn::parse_macro_input!(stream as Sgr256<FG>);
     quote!(#sgr_256).into()
 }
 
@@ -319,7 +622,7 @@ pub fn color_256(stream: TokenStream) -> TokenStream {
 ///     macro syntax.
 #[proc_macro]
 pub fn color_256_bg(stream: TokenStream) -> TokenStream {
-    let sgr_256 = syn::parse_macro_input!(stream as Sgr256<true>);
+    let sgr_256 = syn::parse_macro_input!(stream as Sgr256<BG>);
     quote!(#sgr_256).into()
 }
 
@@ -367,18 +670,65 @@ pub fn color_256_bg(stream: TokenStream) -> TokenStream {
 ///     color_rgb!((0xFF, 127, 0.25); "Orange Text"),
 ///     "\x1B[38;2;255;127;63mOrange Text\x1B[39m",
 /// );
+///
+/// //  CSS Color Name (String Literal):
+/// assert_eq!(
+///     color_rgb!("rebeccapurple"; "Purple Text"),
+///     "\x1B[38;2;102;51;153mPurple Text\x1B[39m",
+/// );
+///
+/// //  CSS Color Name (Identifier):
+/// assert_eq!(
+///     color_rgb!(steelblue; "Blue Text"),
+///     "\x1B[38;2;70;130;180mBlue Text\x1B[39m",
+/// );
+///
+/// //  CSS Color Function:
+/// assert_eq!(
+///     color_rgb!(hsl(210, 40%, 50%); "Blue Text"),
+///     "\x1B[38;2;77;128;179mBlue Text\x1B[39m",
+/// );
+///
+/// //  CSS Color Function (String Literal):
+/// assert_eq!(
+///     color_rgb!("oklch(0.7 0.1 250)"; "Blue Text"),
+///     "\x1B[38;2;109;163;218mBlue Text\x1B[39m",
+/// );
 /// ```
 ///
+/// Color names are those of [CSS Color Module Level 4], and are not
+///     case-sensitive. An unknown name will result in a compile error, which
+///     will suggest a similar name if one exists.
+///
+/// [CSS Color Module Level 4]: https://www.w3.org/TR/css-color-4/#named-colors
+///
+/// Color functions are also written as in CSS, with components separated by
+///     commas or spaces, and converted to 24-bit values at compile-time:
+/// - `rgb(r, g, b)`, with channels from 0 to 255, or percentages.
+/// - `hsl(h, s, l)` and `hsv(h, s, v)`, with saturation, lightness and value
+///   as percentages. The `%` sign is optional.
+/// - `oklch(l, c, h)`, with lightness from 0 to 1, and chroma from 0 to about
+///   0.4. Either may also be a percentage, where `100%` chroma is `0.4`.
+///
+/// Hues are in degrees by default, but may be suffixed with `deg`, `grad`,
+///     `rad` or `turn`. Components outside their valid ranges are clamped, and
+///     OKLCH colors outside the sRGB gamut have their chroma reduced until
+///     they fit, which preserves their lightness and hue.
+///
 /// Only three channels are supported, as an Alpha channel is not applicable to
 ///     text in a terminal. The Integer Literal input format could hold a four
 ///     byte value, since it is a [`u32`], but use of the top byte will result
 ///     in a compile error.
 ///
+/// The color may be followed by an option, `color_256` or `color_16`, to use
+///     the perceptually nearest color of the xterm 256-color palette or of the
+///     16 basic colors instead, for terminals without support for 24-bit color.
+///
 /// Refer to the [crate] documentation for more information on more advanced
 ///     macro syntax.
 #[proc_macro]
 pub fn color_rgb(stream: TokenStream) -> TokenStream {
-    let sgr_rgb = syn::parse_macro_input!(stream as SgrRgb<false>);
+    let sgr_rgb = syn::parse_macro_input!(stream as SgrRgb<FG>);
     quote!(#sgr_rgb).into()
 }
 
@@ -391,11 +741,428 @@ pub fn color_rgb(stream: TokenStream) -> TokenStream {
 ///     macro syntax.
 #[proc_macro]
 pub fn color_rgb_bg(stream: TokenStream) -> TokenStream {
-    let sgr_rgb = syn::parse_macro_input!(stream as SgrRgb<true>);
+    let sgr_rgb = syn::parse_macro_input!(stream as SgrRgb<BG>);
+    quote!(#sgr_rgb).into()
+}
+
+
+/// Color the underline of text with an 8-bit indexed color value, independent
+///     of the text color. Not widely supported.
+///
+/// This does not underline text by itself, and should be combined with
+///     [`sgr_uline!`] or one of its variants.
+///
+/// # Usage
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     uline_color_256!(160; sgr_uline_curly!("Misspelled")),
+///     "\x1B[58;5;160m\x1B[4:3mMisspelled\x1B[24;59m",
+/// );
+/// ```
+///
+/// Refer to the [crate] documentation for more information on more advanced
+///     macro syntax.
+#[proc_macro]
+pub fn uline_color_256(stream: TokenStream) -> TokenStream {
+    let sgr_256 = syn::parse_macro_input!(stream as Sgr256<UL>);
+    quote!(#sgr_256).into()
+}
+
+
+/// Color the underline of text with a 24-bit RGB value, independent of the
+///     text color. Not widely supported.
+///
+/// This does not underline text by itself, and should be combined with
+///     [`sgr_uline!`] or one of its variants.
+///
+/// # Usage
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     uline_color_rgb!(0xFF0000; sgr_uline_curly!("Misspelled")),
+///     "\x1B[58;2;255;0;0m\x1B[4:3mMisspelled\x1B[24;59m",
+/// );
+/// ```
+///
+/// Refer to the [`color_rgb!`] macro for more information on the color format.
+///
+/// Refer to the [crate] documentation for more information on more advanced
+///     macro syntax.
+#[proc_macro]
+pub fn uline_color_rgb(stream: TokenStream) -> TokenStream {
+    let sgr_rgb = syn::parse_macro_input!(stream as SgrRgb<UL>);
     quote!(#sgr_rgb).into()
 }
 
 
+/// Color text with a gradient between two or more colors, computed at
+///     compile-time.
+///
+/// # Usage
+///
+/// The colors are separated by `->`, and are written in any of the formats
+///     accepted by [`color_rgb!`]. They are followed by a semicolon and then a
+///     string literal, which is split into grapheme clusters. Each cluster is
+///     given its own color, evenly spaced from the first color to the last.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr_gradient!(0xFF0000 -> 0x0000FF, linear; "abc"),
+///     concat!(
+///         "\x1B[38;2;255;0;0ma",
+///         "\x1B[38;2;188;0;188mb",
+///         "\x1B[38;2;0;0;255mc\x1B[39m",
+///     ),
+/// );
+/// ```
+///
+/// Options may follow the colors, separated by commas:
+/// - `fg` or `bg`, to color the text or its background. The default is `fg`.
+/// - `oklab` or `linear`, to mix colors in the OKLab color space, with steps
+///   that are perceptually even, or in linear RGB. The default is `oklab`.
+/// - `color_256`, to use the nearest colors of the 256-color palette, for
+///   terminals without 24-bit color.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr_gradient!(red -> blue, bg, color_256; "ab"),
+///     "\x1B[48;5;196ma\x1B[48;5;21mb\x1B[49m",
+/// );
+/// ```
+///
+/// Only Literal Mode is supported, but the `?`, `!` and `*` sigils may be used
+///     as detailed [above](crate#modes). Whitespace is not colored in the
+///     foreground, and consecutive clusters with the same color share a single
+///     control sequence.
+#[proc_macro]
+pub fn sgr_gradient(stream: TokenStream) -> TokenStream {
+    let gradient = syn::parse_macro_input!(stream as SgrGradient);
+    quote!(#gradient).into()
+}
+
+
+/// Apply several SGR attributes with a single control sequence.
+///
+/// # Usage
+///
+/// The first argument of the macro is a comma-separated list of attributes,
+///     followed by a semicolon. An attribute may be the name of any basic color
+///     or style macro in this crate, with the `sgr_` prefix optional for style
+///     macros. Additionally, indexed and RGB colors may be given as values:
+/// - `fg = <rgb>` or `color_rgb = <rgb>`
+/// - `bg = <rgb>` or `color_rgb_bg = <rgb>`
+/// - `color_256 = <index>`
+/// - `color_256_bg = <index>`
+/// - `ul = <rgb>` or `uline_color_rgb = <rgb>`, for underline color
+/// - `uline_color_256 = <index>`
+///
+/// RGB values use the same format as [`color_rgb!`].
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr!(bold, red, bg = 0x223344; "Bold Red on Blue-Grey"),
+///     "\x1B[1;31;48;2;34;51;68mBold Red on Blue-Grey\x1B[22;39;49m",
+/// );
+/// assert_eq!(
+///     sgr!(sgr_italic, color_256 = 173; @* "{} Orange", "Italic"),
+///     "\x1B[3;38;5;173mItalic Orange\x1B[m",
+/// );
+/// ```
+///
+/// Attributes that share a revert sequence, such as [`sgr_bold!`] and
+///     [`sgr_faint!`], are only reverted once.
+/// ```
+/// assert_eq!(
+///     sgr_macros::sgr!(bold, faint; "Both"),
+///     "\x1B[1;2mBoth\x1B[22m",
+/// );
+/// ```
+///
+/// Refer to the [crate] documentation for more information on more advanced
+///     macro syntax.
+#[proc_macro]
+pub fn sgr(stream: TokenStream) -> TokenStream {
+    let sgr_composite = syn::parse_macro_input!(stream as SgrComposite);
+    quote!(#sgr_composite).into()
+}
+
+
+/// Define new macros that apply a fixed set of colors and styles.
+///
+/// # Usage
+///
+/// Each definition is a name, followed by `=` and any number of attributes
+///     joined by `+`. An attribute is the name of a basic color or style macro,
+///     or one of the attributes of [`sgr!`] that takes a value, with the value
+///     in parentheses, such as `bg(0x202020)` or `color_256(173)`. Several
+///     definitions may be separated by semicolons.
+///
+/// Each definition becomes a `macro_rules!` macro, which accepts all the same
+///     mode sigils as the macros of this crate, and opens and reverts all of
+///     its attributes with a single control sequence each, exactly as [`sgr!`]
+///     does.
+/// ```
+/// sgr_macros::define_style! {
+///     warning = bold + yellow + bg(0x202020);
+///     /// Text that should stand out.
+///     highlight = sgr_invert + color_256(173);
+/// }
+///
+/// assert_eq!(
+///     warning!("Caution"),
+///     "\x1B[1;33;48;2;32;32;32mCaution\x1B[22;39;49m",
+/// );
+/// assert_eq!(
+///     warning!(@* "{} warnings", 3),
+///     "\x1B[1;33;48;2;32;32;32m3 warnings\x1B[m",
+/// );
+/// assert_eq!(highlight!(! "new"), "\x1B[7;38;5;173mnew");
+/// ```
+///
+/// Attributes are checked where they are defined, rather than where the new
+///     macro is used. Attributes placed before a name, such as doc comments or
+///     `#[macro_export]`, are applied to the new macro.
+///
+/// Like any `macro_rules!` macro, the new macros may only be used after their
+///     definition.
+///
+/// # Limitations
+///
+/// The new macros invoke [`sgr!`] by its path in this crate, `sgr_macros`,
+///     and so require the crate to be used under that name, rather than
+///     renamed in `Cargo.toml`.
+///
+/// Unlike the other macros of this crate, the new macros are **not** detected
+///     within the input of another macro, which sees only their names, and not
+///     the attributes they apply. An enclosing style that shares a revert
+///     sequence with one of them is therefore not restored after it. Instead,
+///     the enclosing style should be split around the new macro.
+/// ```
+/// use sgr_macros::*;
+///
+/// define_style!(warning = bold + yellow);
+///
+/// //  Red is reverted by `warning!`, and is not restored for "c".
+/// assert_eq!(
+///     red!(concat!("a", warning!("b"), "c")),
+///     "\x1B[31ma\x1B[1;33mb\x1B[22;39mc\x1B[39m",
+/// );
+/// assert_eq!(
+///     concat!(red!("a"), warning!("b"), red!("c")),
+///     "\x1B[31ma\x1B[39m\x1B[1;33mb\x1B[22;39m\x1B[31mc\x1B[39m",
+/// );
+/// ```
+#[proc_macro]
+pub fn define_style(stream: TokenStream) -> TokenStream {
+    let aliases = syn::parse_macro_input!(stream as StyleAliases);
+    quote!(#aliases).into()
+}
+
+
+/// Apply SGR attributes to text with inline markup tags.
+///
+/// # Usage
+///
+/// The macro takes a template literal, in which tags are replaced with control
+///     sequences at compile-time. A tag may be the name of any basic color or
+///     style macro in this crate, with the `sgr_` prefix optional for style
+///     macros, or one of the following:
+/// - `<b>`, `<i>`, `<u>`, and `<s>`, for bold, italic, underlined, and struck
+///   text respectively.
+/// - `<rgb=...>` and `<rgb_bg=...>`, for RGB colors, using the string format
+///   of [`color_rgb!`].
+/// - `<256=...>` and `<256_bg=...>`, for indexed colors.
+/// - `<uline_rgb=...>` and `<uline_256=...>`, for underline colors.
+///
+/// Every tag must be closed by a matching tag, such as `</b>` or `</rgb>`, and
+///     tags must be properly nested. A literal `<` is written as `<<`.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr_markup!("<b><red>error</red></b>: <u>file</u>"),
+///     "\x1B[1;31merror\x1B[39;22m: \x1B[4mfile\x1B[24m",
+/// );
+/// assert_eq!(
+///     sgr_markup!("<rgb=#FF7F00>orange</rgb> <<3"),
+///     "\x1B[38;2;255;127;0morange\x1B[39m <3",
+/// );
+/// ```
+///
+/// Closing a tag that shares a revert sequence with an enclosing tag will apply
+///     the enclosing tag again.
+/// ```
+/// assert_eq!(
+///     sgr_macros::sgr_markup!("<red>a <green>b</green> c</red>"),
+///     "\x1B[31ma \x1B[32mb\x1B[31m c\x1B[39m",
+/// );
+/// ```
+///
+/// As in the other macros, the output of Literal Mode is minimized, removing
+///     any control sequence that would have no effect.
+///
+/// All output mode sigils are supported. The template may contain
+///     placeholders, followed by arguments. In Literal Mode, as in the other
+///     macros, the arguments must be literals, and are filled in at
+///     compile-time.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr_markup!("<b>{}</b>: <u>{path}</u>", "error", path = "/etc/hosts"),
+///     "\x1B[1merror\x1B[22m: \x1B[4m/etc/hosts\x1B[24m",
+/// );
+/// ```
+/// ```
+/// use sgr_macros::*;
+///
+/// fn log(msg: &str, path: &str) -> String {
+///     sgr_markup!(@ "<b><red>error</red></b>: {msg} <u>{path}</u>")
+/// }
+///
+/// assert_eq!(
+///     log("not found", "/etc/hosts"),
+///     "\x1B[1;31merror\x1B[39;22m: not found \x1B[4m/etc/hosts\x1B[24m",
+/// );
+/// ```
+///
+/// By default, a tag left open at the end of the template is a compile error.
+///     With the `*` revert sigil, all formatting is reverted at the end of the
+///     template instead, and with the `!` revert sigil, it is left open.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(sgr_markup!(* "<b><red>alert"), "\x1B[1;31malert\x1B[m");
+/// assert_eq!(sgr_markup!(! "<b><red>alert"), "\x1B[1;31malert");
+/// ```
+///
+/// Refer to the [crate] documentation for more information on more advanced
+///     macro syntax.
+#[proc_macro]
+pub fn sgr_markup(stream: TokenStream) -> TokenStream {
+    let sgr_markup = syn::parse_macro_input!(stream as SgrMarkup);
+    quote!(#sgr_markup).into()
+}
+
+
+/// Link text to a URL, using the OSC 8 control sequence. Terminals without
+///     support for hyperlinks will typically show only the text.
+///
+/// # Usage
+///
+/// The first argument of the macro is the URL, followed by a semicolon. The
+///     text of the link then follows, using all the same mode sigils as
+///     detailed [above](crate#modes). A link may also be given an ID, which
+///     allows a terminal to treat separate links to the same URL as one.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     hyperlink!("https://docs.rs"; "Docs"),
+///     "\x1B]8;;https://docs.rs\x1B\\Docs\x1B]8;;\x1B\\",
+/// );
+/// assert_eq!(
+///     hyperlink!("https://docs.rs", id = "docs"; blue!("Docs")),
+///     concat!(
+///         "\x1B]8;id=docs;https://docs.rs\x1B\\",
+///         "\x1B[34mDocs\x1B[39m",
+///         "\x1B]8;;\x1B\\",
+///     ),
+/// );
+/// ```
+///
+/// A URL that is a string literal is checked at compile-time: it must begin
+///     with a scheme such as `https:` or `file:`, and must not contain spaces
+///     or non-ASCII characters, which should be percent-encoded instead. Any
+///     other expression may be used as the URL or ID in Format, String, or
+///     Const Format Mode, but will not be checked.
+/// ```
+/// use sgr_macros::*;
+///
+/// let path = "file:///tmp/log.txt";
+///
+/// assert_eq!(
+///     hyperlink!(path; @ "{} lines", 20),
+///     "\x1B]8;;file:///tmp/log.txt\x1B\\20 lines\x1B]8;;\x1B\\",
+/// );
+/// ```
+///
+/// Refer to the [crate] documentation for more information on more advanced
+///     macro syntax.
+#[proc_macro]
+pub fn hyperlink(stream: TokenStream) -> TokenStream {
+    let hyperlink = syn::parse_macro_input!(stream as Hyperlink);
+    quote!(#hyperlink).into()
+}
+
+
+/// Define a macro for each semantic role in a theme file, such as `error` or
+///     `path`. Requires the "theme" Cargo feature.
+///
+/// # Usage
+///
+/// The macro takes the path of a TOML file, relative to the directory of the
+///     calling crate's `Cargo.toml`. The file is read at compile-time, and
+///     every key at its top level is a role. The value of a role is the name
+///     of a basic color or style macro, an array of such names, or a table with
+///     any of the following keys:
+/// - `style`, for the names of basic colors or styles.
+/// - `fg`, `bg`, and `ul`, for RGB colors, as a string in any format accepted
+///   by [`color_rgb!`], or as an integer such as `0xFF7F00`.
+/// - `color_256`, `color_256_bg`, and `uline_color_256`, for indexed colors.
+///
+/// As with [`sgr!`], the `sgr_` prefix of a style may be omitted.
+/// ```toml
+/// error = ["bold", "red"]
+/// path = "uline"
+///
+/// [warning]
+/// style = "italic"
+/// fg = "#FFAA00"
+/// ul = "hsl(30, 100%, 50%)"
+/// ```
+///
+/// Each role becomes a `macro_rules!` macro, named with a `theme_` prefix,
+///     which accepts everything that [`sgr!`] accepts after its attributes,
+///     including every sigil. This example reads the theme used by the tests of
+///     this crate, which has the roles above.
+/// ```
+/// #[cfg(feature = "theme")] {
+///     sgr_macros::sgr_theme!("tests/theme.toml");
+///
+///     let (dir, name) = ("/etc", "hosts");
+///
+///     assert_eq!(theme_error!("failed"), "\x1B[1;31mfailed\x1B[22;39m");
+///     assert_eq!(
+///         theme_path!(@ "{}/{}", dir, name),
+///         format!("\x1B[4m{dir}/{name}\x1B[24m"),
+///     );
+/// }
+/// ```
+///
+/// Like any `macro_rules!` macro, the macros of a theme may only be used after
+///     the invocation of this macro, so it should be placed near the top of a
+///     crate. Unlike the other macros of this crate, they are not detected
+///     within the input of another macro, so an enclosing style that shares a
+///     revert sequence with a role is not restored after it.
+///
+/// An invalid entry, such as an unknown style or a malformed color, is a
+///     compile error, which names the line and column of the entry in the file,
+///     and its key.
+#[cfg(feature = "theme")]
+#[proc_macro]
+pub fn sgr_theme(stream: TokenStream) -> TokenStream {
+    let sgr_theme = syn::parse_macro_input!(stream as SgrTheme);
+    quote!(#sgr_theme).into()
+}
+
+
 /// Reset all SGR parameters.
 ///
 /// # Usage
@@ -438,6 +1205,114 @@ pub fn sgr_reset(stream: TokenStream) -> TokenStream {
 }
 
 
+/// Remove all control sequences from a literal, at compile-time.
+///
+/// # Usage
+///
+/// The macro takes a string literal, a call to [`concat!`], or a Literal Mode
+///     invocation of any macro in this crate, nested in any combination. Every
+///     CSI sequence (including SGR sequences) and operating system command
+///     (including hyperlinks) is removed, and the output is a string literal.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(sgr_strip!(green!("ok")), "ok");
+/// assert_eq!(
+///     sgr_strip!(concat!(sgr_bold!("a"), " ", hyperlink!("https://b"; "c"))),
+///     "a c",
+/// );
+/// ```
+///
+/// Any other expression, such as a constant or an invocation in another output
+///     mode, cannot be evaluated at compile-time, and is a compile error.
+#[proc_macro]
+pub fn sgr_strip(stream: TokenStream) -> TokenStream {
+    let strip = syn::parse_macro_input!(stream as SgrStrip);
+    quote!(#strip).into()
+}
+
+
+/// Find the number of columns that a literal occupies in a terminal, at
+///     compile-time.
+///
+/// # Usage
+///
+/// The macro takes the same input as [`sgr_strip!`], and resolves to a `usize`
+///     literal. Control sequences take no space, and the width of each other
+///     character follows its Unicode East Asian Width: wide characters, such as
+///     CJK ideographs and most emoji, take two columns, and zero-width
+///     characters, such as combining marks and zero-width joiners, take none.
+///     An emoji sequence joined by zero-width joiners takes the width of a
+///     single emoji.
+/// ```
+/// use sgr_macros::*;
+///
+/// const WIDTH: usize = sgr_width!(green!("ok"));
+/// assert_eq!(WIDTH, 2);
+///
+/// assert_eq!(sgr_width!(sgr_bold!("日本")), 4);
+/// assert_eq!(sgr_width!(concat!("e\u{301}", red!("!"))), 2);
+/// ```
+///
+/// This is useful for aligning columns of colored text, where [`str::len`]
+///     would also count the bytes of control sequences.
+/// ```
+/// use sgr_macros::*;
+///
+/// const OK: &str = green!("ok");
+/// const PAD: usize = 6 - sgr_width!(green!("ok"));
+///
+/// assert_eq!(format!("[{OK}{:PAD$}]", ""), "[\x1B[32mok\x1B[39m    ]");
+/// ```
+#[proc_macro]
+pub fn sgr_width(stream: TokenStream) -> TokenStream {
+    let width = syn::parse_macro_input!(stream as SgrWidth);
+    quote!(#width).into()
+}
+
+
+/// Convert a literal into HTML, at compile-time.
+///
+/// # Usage
+///
+/// The macro takes the same input as [`sgr_strip!`], and resolves to a string
+///     literal of HTML, in which each run of styled text is a `<span>` and each
+///     hyperlink is an `<a>` element. The text is escaped, and is meant to be
+///     put in an element that preserves whitespace, such as `<pre>`.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr_html!(concat!("a ", sgr_bold!("b"), " c")),
+///     "a <span style=\"font-weight:bold\">b</span> c",
+/// );
+/// ```
+///
+/// By default, every style is written in a `style` attribute. With `classes;`
+///     before the input, attributes and indexed colors are written as classes
+///     for a stylesheet to define instead, such as `sgr-bold` and `sgr-fg-1`.
+///     RGB colors are still written in a `style` attribute.
+/// ```
+/// use sgr_macros::*;
+///
+/// assert_eq!(
+///     sgr_html!(classes; red!("a ", color_rgb!(0x123456; "b"))),
+///     concat!(
+///         "<span class=\"sgr-fg-1\">a </span>",
+///         "<span style=\"color:#123456\">b</span>",
+///     ),
+/// );
+/// ```
+///
+/// Text that is only known at runtime may be converted by the `html` function
+///     of the `sgr_runtime` crate, which this macro uses.
+#[proc_macro]
+pub fn sgr_html(stream: TokenStream) -> TokenStream {
+    let html = syn::parse_macro_input!(stream as SgrHtml);
+    quote!(#html).into()
+}
+
+
 macro_rules! def_sgr {
     ($(
     $(#[$attr:meta])*
@@ -459,6 +1334,16 @@ macro_rules! def_sgr {
             let tokens = sgr_fmt.tokens();
             quote!(#tokens).into()
         })*
+
+        /// Find the opening and closing parameters of a style defined by
+        ///     [`def_sgr!`], by the name of its macro.
+        fn sgr_style(name: &str) -> Option<(String, String)> {
+            $(if name == stringify!($name) {
+                return Some((format!("{}", $start), format!("{}", $end)));
+            })*
+
+            None
+        }
     };
 }
 
@@ -472,6 +1357,14 @@ def_sgr! {
 
     /// Underline text.
     sgr_uline   = 4, 24;
+    /// Underline text with two lines. Not widely supported.
+    sgr_uline_double = "4:2", 24;
+    /// Underline text with a wavy line. Not widely supported.
+    sgr_uline_curly  = "4:3", 24;
+    /// Underline text with a dotted line. Not widely supported.
+    sgr_uline_dotted = "4:4", 24;
+    /// Underline text with a dashed line. Not widely supported.
+    sgr_uline_dashed = "4:5", 24;
     /// Blink text slowly.
     sgr_blink   = 5, 25;
     /// Blink text quickly. Not widely supported.
@@ -486,103 +1379,167 @@ def_sgr! {
     sgr_conceal = 8, 28;
     /// Show text with a horizontal strike, crossing it out.
     sgr_strike  = 9, 29;
-}
 
-def_sgr! {
     /// Superscript. Not widely supported.
     sgr_super   = 73, 75;
     /// Subscript. Not widely supported.
     sgr_sub     = 74, 75;
-}
 
-def_sgr! {
+    //  Every color is closed by the default color of its layer, as defined by
+    //      ECMA-48. Bright colors are an extension, documented by xterm, which
+    //      has no closing parameters of its own.
+
     /// Color the enclosed text black.
     black           = 30, 39;
     /// Color the enclosed text bright black (grey).
-    black_bright    = 90, 99;
+    black_bright    = 90, 39;
     // /// Color the enclosed text grey.
     // ///
     // /// This macro is identical to [`black_bright!`].
-    // grey            = 90, 99;
+    // grey            = 90, 39;
 
     /// Color the enclosed text red.
     red             = 31, 39;
     /// Color the enclosed text bright red.
-    red_bright      = 91, 99;
+    red_bright      = 91, 39;
 
     /// Color the enclosed text green.
     green           = 32, 39;
     /// Color the enclosed text bright green.
-    green_bright    = 92, 99;
+    green_bright    = 92, 39;
 
     /// Color the enclosed text yellow.
     yellow          = 33, 39;
     /// Color the enclosed text bright yellow.
-    yellow_bright   = 93, 99;
+    yellow_bright   = 93, 39;
 
     /// Color the enclosed text blue.
     blue            = 34, 39;
     /// Color the enclosed text bright blue.
-    blue_bright     = 94, 99;
+    blue_bright     = 94, 39;
 
     /// Color the enclosed text magenta.
     magenta         = 35, 39;
     /// Color the enclosed text bright magenta.
-    magenta_bright  = 95, 99;
+    magenta_bright  = 95, 39;
 
     /// Color the enclosed text cyan.
     cyan            = 36, 39;
     /// Color the enclosed text bright cyan.
-    cyan_bright     = 96, 99;
+    cyan_bright     = 96, 39;
 
     /// Color the enclosed text white.
     white           = 37, 39;
     /// Color the enclosed text bright white.
-    white_bright    = 97, 99;
-}
+    white_bright    = 97, 39;
 
-def_sgr! {
     /// Put the enclosed text on a black background.
     black_bg            =  40,  49;
     /// Put the enclosed text on a bright black (grey) background.
-    black_bright_bg     = 100, 109;
+    black_bright_bg     = 100,  49;
     // /// Put the enclosed text on a grey background.
     // ///
     // /// This macro is identical to [`black_bright_bg!`].
-    // grey_bg             = 100, 109;
+    // grey_bg             = 100,  49;
 
     /// Put the enclosed text on a red background.
     red_bg              =  41,  49;
     /// Put the enclosed text on a bright red background.
-    red_bright_bg       = 101, 109;
+    red_bright_bg       = 101,  49;
 
     /// Put the enclosed text on a green background.
     green_bg            =  42,  49;
     /// Put the enclosed text on a bright green background.
-    green_bright_bg     = 102, 109;
+    green_bright_bg     = 102,  49;
 
     /// Put the enclosed text on a yellow background.
     yellow_bg           =  43,  49;
     /// Put the enclosed text on a bright yellow background.
-    yellow_bright_bg    = 103, 109;
+    yellow_bright_bg    = 103,  49;
 
     /// Put the enclosed text on a blue background.
     blue_bg             =  44,  49;
     /// Put the enclosed text on a bright blue background.
-    blue_bright_bg      = 104, 109;
+    blue_bright_bg      = 104,  49;
 
     /// Put the enclosed text on a magenta background.
     magenta_bg          =  45,  49;
     /// Put the enclosed text on a bright magenta background.
-    magenta_bright_bg   = 105, 109;
+    magenta_bright_bg   = 105,  49;
 
     /// Put the enclosed text on a cyan background.
     cyan_bg             =  46,  49;
     /// Put the enclosed text on a bright cyan background.
-    cyan_bright_bg      = 106, 109;
+    cyan_bright_bg      = 106,  49;
 
     /// Put the enclosed text on a white background.
     white_bg            =  47,  49;
     /// Put the enclosed text on a bright white background.
-    white_bright_bg     = 107, 109;
+    white_bright_bg     = 107,  49;
+}
+
+
+macro_rules! def_control {
+    ($(
+    $(#[$attr:meta])*
+    $name:ident = $spec:expr;
+    )*) => {
+        $($(#[$attr])*
+        ///
+        /// Refer to the [crate] documentation for more information on
+        ///     parameters and output modes.
+        #[proc_macro]
+        pub fn $name(stream: TokenStream) -> TokenStream {
+            let parser = |input: syn::parse::ParseStream| {
+                Control::parse(input, &$spec)
+            };
+            let control = syn::parse_macro_input!(stream with parser);
+            quote!(#control).into()
+        })*
+
+        /// Find the form of a control sequence defined by [`def_control!`], by
+        ///     the name of its macro.
+        fn control_spec(name: &str) -> Option<&'static Spec> {
+            $(if name == stringify!($name) {
+                return Some(&$spec);
+            })*
+
+            None
+        }
+    };
+}
+
+def_control! {
+    /// Move the cursor up by a number of lines, which is 1 by default.
+    cursor_up = CURSOR_UP;
+    /// Move the cursor down by a number of lines, which is 1 by default.
+    cursor_down = CURSOR_DOWN;
+    /// Move the cursor right by a number of columns, which is 1 by default.
+    cursor_forward = CURSOR_FORWARD;
+    /// Move the cursor left by a number of columns, which is 1 by default.
+    cursor_back = CURSOR_BACK;
+    /// Move the cursor to a row and a column, counted from 1. Either may be
+    ///     omitted, starting from the end, to move to the first.
+    cursor_position = CURSOR_POSITION;
+    /// Save the position of the cursor.
+    cursor_save = CURSOR_SAVE;
+    /// Restore the position of the cursor, as saved by [`cursor_save!`].
+    cursor_restore = CURSOR_RESTORE;
+    /// Show the cursor.
+    cursor_show = CURSOR_SHOW;
+    /// Hide the cursor.
+    cursor_hide = CURSOR_HIDE;
+
+    /// Erase part of the line of the cursor. The mode is 0 to erase from the
+    ///     cursor to the end, 1 to erase from the start to the cursor, or 2 to
+    ///     erase the whole line. If it is omitted, terminals use 0.
+    erase_line = ERASE_LINE;
+    /// Erase part of the display. The mode is 0 to erase from the cursor to the
+    ///     end, 1 to erase from the start to the cursor, 2 to erase the whole
+    ///     display, or 3 to also erase the scrollback. If it is omitted,
+    ///     terminals use 0.
+    erase_display = ERASE_DISPLAY;
+    /// Limit scrolling to the rows from a top row to a bottom row, counted
+    ///     from 1. Without parameters, the whole display scrolls again.
+    scroll_region = SCROLL_REGION;
 }
@@ -1,19 +1,42 @@
+macro_rules! sgr {
+    ($($param:literal)?) => { concat!("\x1B[", $($param,)? "m") };
+}
+
+
+mod alias;
 mod base;
+mod composite;
+mod control;
+mod eval;
+mod gate;
+mod gradient;
+mod hyperlink;
+mod markup;
+mod minimize;
+mod nested;
+mod pad;
 mod rgb;
+mod template;
+#[cfg(feature = "theme")]
+mod theme;
 
+pub use alias::*;
 pub use base::*;
+pub use composite::*;
+pub use control::*;
+pub use eval::*;
+pub use gradient::*;
+pub use hyperlink::*;
+pub use markup::*;
+#[cfg(feature = "theme")]
+pub use theme::*;
 
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, TokenStreamExt, ToTokens};
+use quote::{quote, ToTokens};
 use syn::{parse::{Parse, ParseStream}, Token};
 use rgb::Rgb;
 
 
-macro_rules! sgr {
-    ($($param:literal)?) => { concat!("\x1B[", $($param,)? "m") };
-}
-
-
 pub struct SgrReset;
 
 impl Parse for SgrReset {
@@ -27,9 +50,82 @@ impl ToTokens for SgrReset {
 }
 
 
-#[inline]
-const fn color_bg<const BG: bool>() -> u8 {
-    if BG { 48 } else { 38 }
+/// The parameter that introduces an extended foreground color.
+pub const FG: u8 = 38;
+/// The parameter that introduces an extended background color.
+pub const BG: u8 = 48;
+/// The parameter that introduces an extended underline color.
+pub const UL: u8 = 58;
+
+
+/// The colors that an RGB color may be written as, for terminals without
+///     support for 24-bit color.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Palette {
+    /// The exact color.
+    #[default]
+    Rgb,
+    /// The perceptually nearest color of the xterm 256-color palette.
+    Color256,
+    /// The perceptually nearest of the 16 basic colors.
+    Color16,
+}
+
+impl Palette {
+    /// Find the palette selected by the name of an option.
+    fn from_option(name: &str) -> Option<Self> {
+        match name {
+            "color_256" => Some(Self::Color256),
+            "color_16" => Some(Self::Color16),
+            _ => None,
+        }
+    }
+}
+
+
+/// Format the opening parameters of an RGB color, as a color of a palette.
+fn fmt_rgb<const BASE: u8>(rgb: &Rgb, palette: Palette) -> String {
+    match palette {
+        Palette::Rgb => {
+            let Rgb { a: _, r, g, b } = rgb;
+            format!("{};2;{};{};{}", BASE, r, g, b)
+        }
+        Palette::Color256 => fmt_256::<BASE>(rgb.nearest_256()),
+        Palette::Color16 => {
+            //  30-37 and 90-97 for foreground, 40-47 and 100-107 for
+            //      background. Underline color has no such parameters, but the
+            //      first 16 indexed colors are the same.
+            let base = BASE - 8;
+
+            match rgb.nearest_16() {
+                index if BASE == UL => fmt_256::<BASE>(index),
+                index @ 0..=7 => format!("{}", base + index),
+                index => format!("{}", base + 60 + index - 8),
+            }
+        }
+    }
+}
+
+
+fn fmt_256<const BASE: u8>(color: u8) -> String {
+    format!("{};5;{}", BASE, color)
+}
+
+
+fn fmt_color_end<const BASE: u8>() -> String {
+    format!("{}", BASE + 1)
+}
+
+
+fn parse_256(input: ParseStream) -> syn::Result<u8> {
+    input.parse::<syn::LitInt>()?.base10_parse()
+}
+
+
+/// Find the opening and closing parameters of a basic color or style, by the
+///     name of its macro. The `sgr_` prefix of a style macro may be omitted.
+fn find_style(name: &str) -> Option<(String, String)> {
+    crate::sgr_style(name).or_else(|| crate::sgr_style(&format!("sgr_{name}")))
 }
 
 
@@ -38,7 +134,33 @@ pub trait SgrData {
     fn fmt_opening(&self) -> String;
     fn fmt_closing(&self) -> String;
 
+    /// Find every SGR parameter that may be reset by the output of this
+    ///     invocation, including by any nested invocations within it.
+    fn fmt_disturbed(&self) -> Vec<String> {
+        let base = self.base();
+
+        let mut params: Vec<String> = match base.behavior.revert {
+            //  Without reverting, a style is deliberately left open, and
+            //      anything within it is meant to be overridden.
+            Revert::None => return Vec::new(),
+            Revert::One => self.fmt_closing()
+                .split(';')
+                .map(String::from)
+                .collect(),
+            Revert::All => vec![String::new()],
+        };
+
+        params.extend(base.disturbed());
+        params
+    }
+
     fn tokens(&self) -> TokenStream {
+        self.expand(self.base().behavior.gated)
+    }
+
+    /// Resolve this invocation into an expression, which may be gated on
+    ///     color support at runtime.
+    fn expand(&self, gated: bool) -> TokenStream {
         let mut tokens = TokenStream::new();
 
         let base = self.base();
@@ -49,35 +171,66 @@ pub trait SgrData {
             Revert::None => String::new(),
         };
 
-        let mut content = TokenStream::new();
-        content.append_all(base.contents.clone());
-
-        let expr = match base.behavior.output {
-            Output::Concat => {
-                assert!(base.template.is_none());
-                quote!(concat!(concat!(#fmt, #content), #end))
+        let closing = self.fmt_closing();
+        let content = &base.contents;
+
+        //  Nested invocations may revert this style before it ends. Wherever
+        //      this happens, the style must be applied again.
+        let template = base.template.as_ref().map(|template| {
+            let restored = nested::restore_template(
+                &template.value(),
+                content,
+                &fmt,
+                &closing,
+            );
+
+            let temp_fmt = format!("{fmt}{restored}{end}");
+            syn::LitStr::new(&temp_fmt, template.span())
+        });
+
+        //  Padded arguments are measured by their visible width.
+        let (template, padded) = match template {
+            Some(template) => {
+                let behavior = Behavior { gated, ..base.behavior.clone() };
+                let (template, padded) = pad::pad(
+                    &template,
+                    content,
+                    &behavior,
+                );
+                (Some(template), padded)
             }
-            Output::ConstFormat => {
-                let template = base.template.as_ref().unwrap();
-                let temp_fmt = format!("{fmt}{}{end}", template.value());
-                let temp_lit = syn::LitStr::new(&temp_fmt, template.span());
-
-                quote!(::const_format::formatcp!(#temp_lit, #content))
-            }
-            Output::Format => {
-                let template = base.template.as_ref().unwrap();
-                let temp_fmt = format!("{fmt}{}{end}", template.value());
-                let temp_lit = syn::LitStr::new(&temp_fmt, template.span());
-
-                quote!(format_args!(#temp_lit, #content))
-            }
-            Output::String => {
-                let template = base.template.as_ref().unwrap();
-                let temp_fmt = format!("{fmt}{}{end}", template.value());
-                let temp_lit = syn::LitStr::new(&temp_fmt, template.span());
-
-                quote!(format!(#temp_lit, #content))
+            None => (None, content.clone()),
+        };
+        let content = &padded;
+
+        let output = &base.behavior.output;
+        let expr = match template {
+            None => {
+                let restored = nested::restore_concat(content, &fmt, &closing);
+                let colored = quote!(concat!(concat!(#fmt, #restored), #end));
+
+                //  If every part of the output is known, the sequences in it
+                //      can be merged, and those that do nothing removed.
+                let colored = match syn::parse2(colored.clone())
+                    .and_then(|expr| eval::literal(&expr))
+                {
+                    Ok(text) => {
+                        let text = minimize::minimize(&text);
+                        syn::LitStr::new(&text, Span::call_site())
+                            .into_token_stream()
+                    }
+                    Err(..) => colored,
+                };
+
+                if gated {
+                    let plain = gate::plain_concat(content);
+                    quote!((#colored, concat!(#plain)))
+                } else {
+                    colored
+                }
             }
+            Some(temp_lit) if gated => gate::expand(output, &temp_lit, content),
+            Some(temp_lit) => output.expand(&temp_lit, content),
         };
 
         tokens.extend(expr);
@@ -105,61 +258,66 @@ impl ToTokens for SgrFormat {
 }
 
 
-pub struct SgrRgb<const BG: bool> {
+pub struct SgrRgb<const BASE: u8> {
     base: SgrBase,
     rgb: Rgb,
+    palette: Palette,
 }
 
-impl<const BG: bool> SgrData for SgrRgb<BG> {
+impl<const BASE: u8> SgrData for SgrRgb<BASE> {
     fn base(&self) -> &SgrBase { &self.base }
 
     fn fmt_opening(&self) -> String {
-        let Rgb { a: _, r, g, b } = &self.rgb;
-        format!("{};2;{};{};{}", color_bg::<BG>(), r, g, b)
-    }
-
-    fn fmt_closing(&self) -> String {
-        format!("{}", color_bg::<BG>() + 1)
+        fmt_rgb::<BASE>(&self.rgb, self.palette)
     }
+    fn fmt_closing(&self) -> String { fmt_color_end::<BASE>() }
 }
 
-impl<const BG: bool> Parse for SgrRgb<BG> {
+impl<const BASE: u8> Parse for SgrRgb<BASE> {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let rgb: Rgb = input.parse()?;
+        let palette = if input.parse::<Token![,]>().is_ok() {
+            let option: syn::Ident = input.parse()?;
+
+            Palette::from_option(&option.to_string()).ok_or_else(|| {
+                syn::Error::new(
+                    option.span(),
+                    format!("unknown RGB color option `{option}`; expected \
+                    `color_256` or `color_16`"),
+                )
+            })?
+        } else {
+            Palette::Rgb
+        };
         let _: Token![;] = input.parse()?;
         let base: SgrBase = input.parse()?;
 
-        Ok(Self { base, rgb })
+        Ok(Self { base, rgb, palette })
     }
 }
 
-impl<const BG: bool> ToTokens for SgrRgb<BG> {
+impl<const BASE: u8> ToTokens for SgrRgb<BASE> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.extend(self.tokens())
     }
 }
 
 
-pub struct Sgr256<const BG: bool> {
+pub struct Sgr256<const BASE: u8> {
     base: SgrBase,
     color: u8,
 }
 
-impl<const BG: bool> SgrData for Sgr256<BG> {
+impl<const BASE: u8> SgrData for Sgr256<BASE> {
     fn base(&self) -> &SgrBase { &self.base }
 
-    fn fmt_opening(&self) -> String {
-        format!("{};5;{}", color_bg::<BG>(), self.color)
-    }
-
-    fn fmt_closing(&self) -> String {
-        format!("{}", color_bg::<BG>() + 1)
-    }
+    fn fmt_opening(&self) -> String { fmt_256::<BASE>(self.color) }
+    fn fmt_closing(&self) -> String { fmt_color_end::<BASE>() }
 }
 
-impl<const BG: bool> Parse for Sgr256<BG> {
+impl<const BASE: u8> Parse for Sgr256<BASE> {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let color: u8 = input.parse::<syn::LitInt>()?.base10_parse()?;
+        let color: u8 = parse_256(input)?;
         let _: Token![;] = input.parse()?;
         let base: SgrBase = input.parse()?;
 
@@ -167,7 +325,7 @@ impl<const BG: bool> Parse for Sgr256<BG> {
     }
 }
 
-impl<const BG: bool> ToTokens for Sgr256<BG> {
+impl<const BASE: u8> ToTokens for Sgr256<BASE> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.extend(self.tokens())
     }
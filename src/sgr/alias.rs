@@ -0,0 +1,101 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Token,
+};
+use super::Attr;
+
+
+/// Define a `macro_rules!` macro that applies a fixed set of attributes with
+///     [`sgr!`], forwarding everything else it is given, including sigils.
+///
+/// [`sgr!`]: crate::sgr!
+pub fn define(
+    outer: &[syn::Attribute],
+    name: &syn::Ident,
+    attrs: &[TokenStream],
+) -> TokenStream {
+    quote! {
+        #(#outer)*
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            ($($t:tt)*) => {
+                ::sgr_macros::sgr!(#(#attrs),*; $($t)*)
+            };
+        }
+    }
+}
+
+
+/// A single named style, such as `warning = bold + yellow + bg(0x202020)`.
+pub struct StyleAlias {
+    outer: Vec<syn::Attribute>,
+    name: syn::Ident,
+    attrs: Vec<TokenStream>,
+}
+
+impl Parse for StyleAlias {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let outer = input.call(syn::Attribute::parse_outer)?;
+        let name: syn::Ident = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let mut attrs = Vec::new();
+
+        loop {
+            let key: syn::Ident = input.parse()?;
+
+            //  A value is given in parentheses, but `sgr!` takes it after `=`.
+            let attr = if input.peek(syn::token::Paren) {
+                let inner;
+                syn::parenthesized!(inner in input);
+                let value: TokenStream = inner.parse()?;
+
+                quote!(#key = #value)
+            } else {
+                quote!(#key)
+            };
+
+            //  Check the attribute now, so that an error points to the
+            //      definition, rather than to every use of it.
+            syn::parse2::<Attr>(attr.clone())?;
+            attrs.push(attr);
+
+            if input.parse::<Token![+]>().is_err() {
+                if !input.is_empty() && !input.peek(Token![;]) {
+                    return Err(input.error(
+                        "expected `+` between attributes, or `;` after a \
+                        definition",
+                    ));
+                }
+
+                break;
+            }
+        }
+
+        Ok(Self { outer, name, attrs })
+    }
+}
+
+
+/// Any number of named styles, separated by semicolons.
+pub struct StyleAliases(Vec<StyleAlias>);
+
+impl Parse for StyleAliases {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let aliases = Punctuated::<StyleAlias, Token![;]>::parse_terminated(
+            input,
+        )?;
+
+        Ok(Self(aliases.into_iter().collect()))
+    }
+}
+
+impl ToTokens for StyleAliases {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for StyleAlias { outer, name, attrs } in &self.0 {
+            tokens.extend(define(outer, name, attrs));
+        }
+    }
+}
@@ -1,17 +1,30 @@
-use proc_macro2::{Span, TokenStream, TokenTree};
-use quote::TokenStreamExt;
-use syn::{parse::{Parse, ParseStream}, Token};
-use super::SgrFormat;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{quote, TokenStreamExt, ToTokens};
+use sgr_runtime::Stream;
+use syn::{parse::{Parse, ParseStream, Parser}, Token};
+use super::{
+    eval,
+    nested::Args,
+    pad,
+    template::{interpolate, placeholders},
+    SgrFormat,
+};
 
 
+type SigilGated = Token![?];
 type SigilOutputConstFormat = Token![#];
 type SigilOutputFormat = Token![%];
+type SigilOutputLazy = Token![&];
+type SigilOutputPrint = Token![>];
+type SigilOutputPrintLine = Token![>>];
+type SigilOutputPrintStderr = Token![!];
 type SigilOutputString = Token![@];
+type SigilOutputWrite = Token![>];
 type SigilRevertAll = Token![*];
 type SigilRevertOff = Token![!];
 
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub enum Output {
     /// Resolves to a call to [`concat!`].
     ///
@@ -29,28 +42,83 @@ pub enum Output {
     ///
     /// Output: [`String`]
     String,
+    /// Resolves to a call to [`write!`], on the target expression given after
+    ///     the sigil.
+    ///
+    /// Output: [`fmt::Result`], or [`io::Result`] for an [`io::Write`] target
+    ///
+    /// [`fmt::Result`]: std::fmt::Result
+    /// [`io::Result`]: std::io::Result
+    /// [`io::Write`]: std::io::Write
+    Write(TokenStream),
+    /// Resolves to a call to [`print!`], [`println!`], [`eprint!`], or
+    ///     [`eprintln!`].
+    ///
+    /// Output: `()`
+    Print {
+        stream: Stream,
+        newline: bool,
+    },
+    /// Resolves to a closure that calls [`write!`], wrapped in a value that
+    ///     calls it whenever it is displayed. With `move`, the closure takes
+    ///     ownership of its arguments.
+    ///
+    /// Output: `sgr_runtime::Lazy`, which implements [`Display`] and [`Debug`]
+    ///
+    /// [`Display`]: std::fmt::Display
+    /// [`Debug`]: std::fmt::Debug
+    Lazy {
+        by_move: bool,
+    },
 }
 
 impl Output {
-    /*pub const fn accepts_template(&self) -> bool {
+    pub const fn needs_template(&self) -> bool {
         match self {
             Self::Concat => false,
             Self::ConstFormat => true,
             Self::Format => true,
             Self::String => true,
+            Self::Write(..) => true,
+            Self::Print { .. } => true,
+            Self::Lazy { .. } => true,
         }
-    }*/
+    }
 
-    pub const fn needs_template(&self) -> bool {
+    pub const fn has_sigil(&self) -> bool { !matches!(self, Self::Concat) }
+
+    /// Resolve a finished template and its arguments into an expression of
+    ///     this output mode. In Literal Mode, the template is the output.
+    pub fn expand(
+        &self,
+        template: &syn::LitStr,
+        args: &TokenStream,
+    ) -> TokenStream {
         match self {
-            Self::Concat => false,
-            Self::ConstFormat => true,
-            Self::Format => true,
-            Self::String => true,
+            Self::Concat => template.to_token_stream(),
+            Self::ConstFormat => {
+                quote!(::const_format::formatcp!(#template, #args))
+            }
+            Self::Format => quote!(format_args!(#template, #args)),
+            Self::String => quote!(format!(#template, #args)),
+            Self::Write(target) => quote!(write!(#target, #template, #args)),
+            Self::Print { stream, newline } => match (stream, newline) {
+                (Stream::Stdout, false) => quote!(print!(#template, #args)),
+                (Stream::Stdout, true) => quote!(println!(#template, #args)),
+                (Stream::Stderr, false) => quote!(eprint!(#template, #args)),
+                (Stream::Stderr, true) => quote!(eprintln!(#template, #args)),
+            },
+            Self::Lazy { by_move } => {
+                //  The formatter must not shadow any argument of the same name.
+                let f = Ident::new("formatter", Span::mixed_site());
+                let capture = by_move.then(|| quote!(move));
+
+                quote!(::sgr_runtime::Lazy::new(
+                    #capture |#f| write!(#f, #template, #args)
+                ))
+            }
         }
     }
-
-    pub fn has_sigil(&self) -> bool { *self != Self::Concat }
 }
 
 impl Parse for Output {
@@ -68,6 +136,43 @@ impl Parse for Output {
             }
 
             Ok(Self::ConstFormat)
+        } else if input.peek(syn::LitInt) && input.peek2(Token![>]) {
+            //  Like a shell redirection, `1` is standard output, and `2` is
+            //      standard error.
+            let fd: syn::LitInt = input.parse()?;
+            let stream = match fd.base10_digits() {
+                "1" => Stream::Stdout,
+                "2" => Stream::Stderr,
+                _ => return Err(syn::Error::new(
+                    fd.span(),
+                    "print sigil must be preceded by `1` for standard output, \
+                    or `2` for standard error",
+                )),
+            };
+            let newline = input.parse::<SigilOutputPrintLine>().is_ok();
+
+            if !newline {
+                let _: SigilOutputPrint = input.parse()?;
+            }
+
+            Ok(Self::Print { stream, newline })
+        } else if input.parse::<SigilOutputLazy>().is_ok() {
+            let by_move = input.parse::<Token![move]>().is_ok();
+            Ok(Self::Lazy { by_move })
+        } else if input.parse::<SigilOutputPrintLine>().is_ok() {
+            //  Without a number, `>>!` is the short form of `2>>`. A revert
+            //      sigil may follow either, as in `>>!!` or `1>>!`.
+            let stream = match input.parse::<SigilOutputPrintStderr>() {
+                Ok(..) => Stream::Stderr,
+                Err(..) => Stream::Stdout,
+            };
+
+            Ok(Self::Print { stream, newline: true })
+        } else if input.parse::<SigilOutputWrite>().is_ok() {
+            let target: syn::Expr = input.parse()?;
+            let _: Token![;] = input.parse()?;
+
+            Ok(Self::Write(target.into_token_stream()))
         } else {
             Ok(Self::Concat)
         }
@@ -99,23 +204,36 @@ impl Parse for Revert {
 }
 
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Behavior {
     pub output: Output,
+    /// Whether control sequences are only written if color is enabled at
+    ///     runtime, as determined by the `sgr_runtime` crate.
+    pub gated: bool,
     pub revert: Revert,
 }
 
 impl Parse for Behavior {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let output: Output = input.parse()?;
+        let gated = match input.parse::<SigilGated>() {
+            Ok(sigil) if matches!(output, Output::ConstFormat) => {
+                return Err(syn::Error::new(
+                    sigil.span,
+                    "Const Format Mode cannot be gated at runtime",
+                ));
+            }
+            Ok(..) => true,
+            Err(..) => false,
+        };
         let revert: Revert = input.parse()?;
 
-        if output.has_sigil() || revert.has_sigil() {
+        if output.has_sigil() || gated || revert.has_sigil() {
             //  Accept, but do not require, a comma after mode sigils.
             input.parse::<Token![,]>().ok();
         }
 
-        Ok(Self { output, revert })
+        Ok(Self { output, gated, revert })
     }
 }
 
@@ -131,6 +249,26 @@ impl SgrBase {
     pub const fn into_format(self, start: String, end: String) -> SgrFormat {
         SgrFormat { base: self, opening: start, closing: end }
     }
+
+    /// Fill a Literal Mode template at compile-time, returning the contents
+    ///     that it resolves to. Every argument must be a literal, or another
+    ///     expression that [`eval::literal`] can evaluate.
+    pub fn fill_template(
+        template: &syn::LitStr,
+        contents: &TokenStream,
+        behavior: &Behavior,
+    ) -> syn::Result<TokenStream> {
+        let args = Args::parse_terminated.parse2(contents.clone())?;
+
+        for arg in &args {
+            eval::literal(&arg.expr)?;
+        }
+
+        let (template, contents) = pad::pad(template, contents, behavior);
+        let args = Args::parse_terminated.parse2(contents)?;
+
+        interpolate(&template, &args)
+    }
 }
 
 impl Parse for SgrBase {
@@ -147,25 +285,26 @@ impl Parse for SgrBase {
             } else {
                 template = Some(syn::LitStr::new("{}", Span::call_site()));
                 get_more = true;
-            }/*
-        } else if behavior.output.accepts_template() {
-            //  Output mode does not require a template literal, can still
-            //      accept one.
+            }
+        } else {
+            //  Literal Mode does not require a template literal, but can still
+            //      accept one. A literal is only taken as a template if it has
+            //      placeholders and arguments follow it; otherwise, it is text
+            //      to be concatenated.
             let fork = input.fork();
-            let template_next = fork.parse::<syn::LitStr>().is_ok();
-            let then_comma = fork.peek(Token![,]);
+            let template_next = fork.parse::<syn::LitStr>().is_ok_and(|t| {
+                placeholders(&t.value()).is_ok_and(|holes| !holes.is_empty())
+            });
+            let then_args = fork.parse::<Token![,]>().is_ok()
+                && !fork.is_empty();
 
-            if template_next && then_comma {
+            if template_next && then_args {
                 template = Some(input.parse::<syn::LitStr>()?);
                 get_more = input.parse::<Token![,]>().is_ok();
             } else {
                 template = None;
                 get_more = true;
-            }*/
-        } else {
-            //  Output mode does not accept a template literal.
-            template = None;
-            get_more = true;
+            }
         }
 
         let mut contents = TokenStream::new();
@@ -176,6 +315,15 @@ impl Parse for SgrBase {
             }
         }
 
+        if let (Output::Concat, Some(template)) =
+            (&behavior.output, &template)
+        {
+            //  In Literal Mode, the output must be a single literal, so the
+            //      template is filled in now, and concatenated instead.
+            let contents = Self::fill_template(template, &contents, &behavior)?;
+            return Ok(Self { behavior, template: None, contents });
+        }
+
         Ok(Self { behavior, template, contents })
     }
 }
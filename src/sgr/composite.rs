@@ -0,0 +1,124 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{parse::{Parse, ParseStream}, punctuated::Punctuated, Token};
+use super::{
+    find_style,
+    fmt_256,
+    fmt_color_end,
+    fmt_rgb,
+    parse_256,
+    rgb::Rgb,
+    BG,
+    FG,
+    Palette,
+    SgrBase,
+    SgrData,
+    UL,
+};
+
+
+/// A single attribute of a composite SGR sequence.
+pub struct Attr {
+    pub opening: String,
+    pub closing: String,
+}
+
+impl Parse for Attr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: syn::Ident = input.parse()?;
+        let key = name.to_string();
+
+        if input.parse::<Token![=]>().is_ok() {
+            let (opening, closing) = match key.as_str() {
+                "fg" | "color_rgb" => {
+                    let rgb: Rgb = input.parse()?;
+                    (fmt_rgb::<FG>(&rgb, Palette::Rgb), fmt_color_end::<FG>())
+                }
+                "bg" | "color_rgb_bg" => {
+                    let rgb: Rgb = input.parse()?;
+                    (fmt_rgb::<BG>(&rgb, Palette::Rgb), fmt_color_end::<BG>())
+                }
+                "color_256" => {
+                    let color: u8 = parse_256(input)?;
+                    (fmt_256::<FG>(color), fmt_color_end::<FG>())
+                }
+                "color_256_bg" => {
+                    let color: u8 = parse_256(input)?;
+                    (fmt_256::<BG>(color), fmt_color_end::<BG>())
+                }
+                "ul" | "uline_color_rgb" => {
+                    let rgb: Rgb = input.parse()?;
+                    (fmt_rgb::<UL>(&rgb, Palette::Rgb), fmt_color_end::<UL>())
+                }
+                "uline_color_256" => {
+                    let color: u8 = parse_256(input)?;
+                    (fmt_256::<UL>(color), fmt_color_end::<UL>())
+                }
+                _ => return Err(syn::Error::new(
+                    name.span(),
+                    format!("attribute `{key}` does not take a value"),
+                )),
+            };
+
+            Ok(Self { opening, closing })
+        } else if let Some((opening, closing)) = find_style(&key) {
+            Ok(Self { opening, closing })
+        } else {
+            Err(syn::Error::new(
+                name.span(),
+                format!("unknown SGR attribute `{key}`"),
+            ))
+        }
+    }
+}
+
+
+/// Several attributes, applied together in a single control sequence.
+pub struct SgrComposite {
+    base: SgrBase,
+    attrs: Vec<Attr>,
+}
+
+impl SgrData for SgrComposite {
+    fn base(&self) -> &SgrBase { &self.base }
+
+    fn fmt_opening(&self) -> String {
+        let params: Vec<&str> = self.attrs.iter()
+            .map(|attr| attr.opening.as_str())
+            .collect();
+
+        params.join(";")
+    }
+
+    fn fmt_closing(&self) -> String {
+        //  Several attributes may share a closing parameter. Only one of each
+        //      is needed.
+        let mut params: Vec<&str> = Vec::with_capacity(self.attrs.len());
+
+        for attr in &self.attrs {
+            if !params.contains(&attr.closing.as_str()) {
+                params.push(&attr.closing);
+            }
+        }
+
+        params.join(";")
+    }
+}
+
+impl Parse for SgrComposite {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = Punctuated::<Attr, Token![,]>::parse_separated_nonempty(
+            input,
+        )?;
+        let _: Token![;] = input.parse()?;
+        let base: SgrBase = input.parse()?;
+
+        Ok(Self { base, attrs: attrs.into_iter().collect() })
+    }
+}
+
+impl ToTokens for SgrComposite {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(self.tokens())
+    }
+}
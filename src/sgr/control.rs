@@ -0,0 +1,286 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{parse::ParseStream, punctuated::Punctuated, Token};
+use super::{
+    template::{placeholders, ArgRef},
+    Behavior,
+    Output,
+    Revert,
+};
+
+
+/// A numeric parameter of a control sequence, and the values it may take.
+pub struct Param {
+    name: &'static str,
+    min: u16,
+    max: u16,
+}
+
+const COUNT: Param = Param { name: "count", min: 1, max: u16::MAX };
+const ROW: Param = Param { name: "row", min: 1, max: u16::MAX };
+const COLUMN: Param = Param { name: "column", min: 1, max: u16::MAX };
+const TOP: Param = Param { name: "top", min: 1, max: u16::MAX };
+const BOTTOM: Param = Param { name: "bottom", min: 1, max: u16::MAX };
+
+
+/// The form of a CSI sequence, other than SGR. Any number of its parameters
+///     may be given, starting from the first; the rest take their defaults.
+pub struct Spec {
+    /// Written before the parameters, such as the `?` of a private mode.
+    prefix: &'static str,
+    params: &'static [Param],
+    /// Whether each parameter must be greater than the one before it.
+    ascending: bool,
+    final_byte: char,
+}
+
+impl Spec {
+    const fn new(params: &'static [Param], final_byte: char) -> Self {
+        Self { prefix: "", params, ascending: false, final_byte }
+    }
+}
+
+pub const CURSOR_UP: Spec = Spec::new(&[COUNT], 'A');
+pub const CURSOR_DOWN: Spec = Spec::new(&[COUNT], 'B');
+pub const CURSOR_FORWARD: Spec = Spec::new(&[COUNT], 'C');
+pub const CURSOR_BACK: Spec = Spec::new(&[COUNT], 'D');
+pub const CURSOR_POSITION: Spec = Spec::new(&[ROW, COLUMN], 'H');
+pub const CURSOR_SAVE: Spec = Spec::new(&[], 's');
+pub const CURSOR_RESTORE: Spec = Spec::new(&[], 'u');
+pub const CURSOR_SHOW: Spec = Spec { prefix: "?25", ..Spec::new(&[], 'h') };
+pub const CURSOR_HIDE: Spec = Spec { prefix: "?25", ..Spec::new(&[], 'l') };
+pub const ERASE_LINE: Spec = Spec::new(
+    &[Param { name: "mode", min: 0, max: 2 }],
+    'K',
+);
+pub const ERASE_DISPLAY: Spec = Spec::new(
+    &[Param { name: "mode", min: 0, max: 3 }],
+    'J',
+);
+pub const SCROLL_REGION: Spec = Spec {
+    ascending: true,
+    ..Spec::new(&[TOP, BOTTOM], 'r')
+};
+
+
+/// Read the value of an integer literal, if an expression is one. A negative
+///     literal is read as zero, which is below the range of every parameter
+///     that could be given one.
+fn int_value(expr: &syn::Expr) -> Option<syn::Result<u64>> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(int), .. }) => {
+            Some(int.base10_parse())
+        }
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(..),
+            expr,
+            ..
+        }) => int_value(expr).map(|_| Ok(0)),
+        syn::Expr::Group(group) => int_value(&group.expr),
+        syn::Expr::Paren(paren) => int_value(&paren.expr),
+        _ => None,
+    }
+}
+
+
+/// Find the value of each parameter that a template writes, in order, with the
+///     tokens to point to if it is wrong. A value is only known if it is
+///     written in the template, or if its placeholder is the whole parameter,
+///     and refers to an integer literal.
+fn written(
+    template: &syn::LitStr,
+    items: &Punctuated<syn::Expr, Token![,]>,
+    values: &[Option<u64>],
+) -> Vec<(Option<u64>, TokenStream)> {
+    let text = template.value();
+    let holes = placeholders(&text).unwrap_or_default();
+    let inside = |pos: usize| holes.iter()
+        .any(|hole| (hole.start..hole.end).contains(&pos));
+
+    //  Parameters are separated by semicolons outside of any placeholder.
+    let mut starts = vec![0];
+    let mut ends = Vec::new();
+
+    for (pos, _) in text.match_indices(';').filter(|(pos, _)| !inside(*pos)) {
+        ends.push(pos);
+        starts.push(pos + 1);
+    }
+
+    ends.push(text.len());
+
+    starts.into_iter().zip(ends).map(|(start, end)| {
+        let hole = holes.iter()
+            .find(|hole| hole.start == start && hole.end == end);
+
+        match hole.map(|hole| &hole.arg) {
+            Some(ArgRef::Index(i)) if *i < items.len() => {
+                (values[*i], items[*i].to_token_stream())
+            }
+            Some(..) => (None, template.to_token_stream()),
+            None => (text[start..end].parse().ok(), template.to_token_stream()),
+        }
+    }).collect()
+}
+
+
+/// A CSI sequence that controls the cursor or the screen, rather than the
+///     style of text.
+pub struct Control {
+    output: Output,
+    template: syn::LitStr,
+    args: Punctuated<syn::Expr, Token![,]>,
+}
+
+impl Control {
+    pub fn parse(input: ParseStream, spec: &Spec) -> syn::Result<Self> {
+        let span = input.span();
+        let Behavior { output, gated, revert } = input.parse()?;
+
+        if gated || revert != Revert::One {
+            return Err(syn::Error::new(
+                span,
+                "a control sequence other than SGR cannot be gated or \
+                reverted; only an output mode sigil is accepted",
+            ));
+        }
+
+        let template: Option<syn::LitStr> = if output.needs_template()
+            && input.peek(syn::LitStr)
+        {
+            let template = input.parse()?;
+            let _ = input.parse::<Token![,]>();
+            Some(template)
+        } else {
+            None
+        };
+
+        let items: Punctuated<syn::Expr, Token![,]> =
+            Punctuated::parse_terminated(input)?;
+        let mut values: Vec<Option<u64>> = Vec::new();
+
+        for (i, expr) in items.iter().enumerate() {
+            let Some(param) = spec.params.get(i) else {
+                let expected = match spec.params.len() {
+                    0 => String::from("no parameters"),
+                    1 => String::from("at most 1 parameter"),
+                    n => format!("at most {n} parameters"),
+                };
+
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    format!("unexpected parameter; expected {expected}"),
+                ));
+            };
+
+            let value = match int_value(expr) {
+                Some(value) => Some(value?),
+                None if matches!(output, Output::Concat) => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        format!("a {} that is not an integer literal \
+                        requires an output mode sigil such as `%` or `@`",
+                        param.name),
+                    ));
+                }
+                None => None,
+            };
+
+            if let Some(value) = value {
+                let (min, max) = (u64::from(param.min), u64::from(param.max));
+
+                if !(min..=max).contains(&value) {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        format!("{} must be from {min} to {max}", param.name),
+                    ));
+                }
+            }
+
+            values.push(value);
+        }
+
+        let params = match &template {
+            Some(template) => {
+                //  The template takes the place of the parameters, so outside
+                //      of its placeholders, only parameter bytes are allowed.
+                let text = template.value();
+                let mut outside = text.clone();
+
+                for placeholder in placeholders(&text).unwrap_or_default()
+                    .iter()
+                    .rev()
+                {
+                    let range = placeholder.start..placeholder.end;
+                    outside.replace_range(range, "");
+                }
+
+                if let Some(c) = outside.chars()
+                    .find(|c| !('0'..='?').contains(c))
+                {
+                    return Err(syn::Error::new(
+                        template.span(),
+                        format!("a control sequence cannot contain {c:?} \
+                        among its parameters"),
+                    ));
+                }
+
+                text
+            }
+            None => values.iter()
+                .map(|value| match value {
+                    Some(value) => value.to_string(),
+                    None => String::from("{}"),
+                })
+                .collect::<Vec<_>>()
+                .join(";"),
+        };
+
+        if spec.ascending {
+            let written = match &template {
+                Some(template) => written(template, &items, &values),
+                None => values.iter().copied()
+                    .zip(items.iter().map(ToTokens::to_token_stream))
+                    .collect(),
+            };
+
+            for (i, pair) in written.windows(2).enumerate() {
+                let [(Some(prev), _), (Some(value), tokens)] = pair else {
+                    continue;
+                };
+
+                if value <= prev {
+                    let names = (spec.params.get(i), spec.params.get(i + 1));
+                    let (Some(prev), Some(param)) = names else { continue };
+
+                    return Err(syn::Error::new_spanned(
+                        tokens,
+                        format!("{} must be greater than {}",
+                        param.name, prev.name),
+                    ));
+                }
+            }
+        }
+
+        //  Literal arguments are written into the sequence, unless a template
+        //      refers to them.
+        let args = match template {
+            Some(..) => items,
+            None => items.into_iter().zip(&values)
+                .filter(|(_, value)| value.is_none())
+                .map(|(expr, _)| expr)
+                .collect(),
+        };
+
+        let text = format!("\x1B[{}{params}{}", spec.prefix, spec.final_byte);
+        let template = syn::LitStr::new(&text, Span::call_site());
+
+        Ok(Self { output, template, args })
+    }
+}
+
+impl ToTokens for Control {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let args = &self.args;
+        tokens.extend(self.output.expand(&self.template, &quote!(#args)));
+    }
+}
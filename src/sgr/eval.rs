@@ -0,0 +1,163 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::ToTokens;
+use sgr_runtime::Markup;
+use syn::{parse::{Parse, ParseStream, Parser}, Token};
+use unicode_width::UnicodeWidthStr;
+use super::{
+    gate,
+    nested::{concat, hyperlink, invocation, macro_name, Items},
+    Control,
+    Output,
+    SgrGradient,
+    SgrMarkup,
+    SgrReset,
+};
+
+
+/// Expand an invocation of a macro from this crate, if it has an output that
+///     may be a literal.
+fn expansion(expr: &syn::Expr) -> Option<syn::Result<TokenStream>> {
+    if let Some(sgr) = invocation(expr) {
+        let behavior = &sgr.base().behavior;
+        let literal = matches!(behavior.output, Output::Concat);
+
+        return Some(if !literal || behavior.gated {
+            Err(syn::Error::new_spanned(
+                expr,
+                "only a Literal Mode invocation, without a `?` sigil, can be \
+                evaluated at compile-time",
+            ))
+        } else {
+            Ok(sgr.tokens())
+        });
+    } else if let Some(link) = hyperlink(expr) {
+        return Some(Ok(link.into_token_stream()));
+    }
+
+    let syn::Expr::Macro(m) = expr else { return None };
+    let tokens = m.mac.tokens.clone();
+
+    match macro_name(&m.mac)?.as_str() {
+        "sgr_gradient" => Some(syn::parse2::<SgrGradient>(tokens)
+            .map(ToTokens::into_token_stream)),
+        "sgr_markup" => Some(syn::parse2::<SgrMarkup>(tokens)
+            .map(ToTokens::into_token_stream)),
+        "sgr_reset" => Some(syn::parse2::<SgrReset>(tokens)
+            .map(ToTokens::into_token_stream)),
+        "sgr_strip" => Some(syn::parse2::<SgrStrip>(tokens)
+            .map(ToTokens::into_token_stream)),
+        name => {
+            let spec = crate::control_spec(name)?;
+            let parser = |input: ParseStream| Control::parse(input, spec);
+            Some(parser.parse2(tokens).map(ToTokens::into_token_stream))
+        }
+    }
+}
+
+
+/// Evaluate an expression that resolves to a string literal, at compile-time.
+///     This may be a literal, a call to [`concat!`], or a Literal Mode
+///     invocation of a macro from this crate, nested in any combination.
+pub fn literal(expr: &syn::Expr) -> syn::Result<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            syn::Lit::Str(s) => Ok(s.value()),
+            syn::Lit::Char(c) => Ok(c.value().to_string()),
+            syn::Lit::Int(i) => Ok(i.base10_digits().to_string()),
+            syn::Lit::Float(f) => Ok(f.base10_digits().to_string()),
+            syn::Lit::Bool(b) => Ok(b.value.to_string()),
+            _ => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+        },
+        syn::Expr::Group(group) => literal(&group.expr),
+        syn::Expr::Paren(paren) => literal(&paren.expr),
+        _ => {
+            if let Some(m) = concat(expr) {
+                let items = Items::parse_terminated.parse2(m.mac.tokens)?;
+                return items.iter().map(literal).collect();
+            }
+
+            let tokens = match expansion(expr) {
+                Some(tokens) => tokens?,
+                None => return Err(syn::Error::new_spanned(
+                    expr,
+                    "cannot be evaluated at compile-time; expected a string \
+                    literal, `concat!`, or a Literal Mode invocation of a \
+                    macro from this crate",
+                )),
+            };
+
+            //  Errors within an expansion are reported at the invocation.
+            syn::parse2::<syn::Expr>(tokens)
+                .and_then(|expanded| literal(&expanded))
+                .map_err(|err| syn::Error::new_spanned(expr, err))
+        }
+    }
+}
+
+
+/// The text of a literal, without control sequences.
+pub struct SgrStrip(String);
+
+impl Parse for SgrStrip {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let expr: syn::Expr = input.parse()?;
+        Ok(Self(gate::strip(&literal(&expr)?)))
+    }
+}
+
+impl ToTokens for SgrStrip {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        syn::LitStr::new(&self.0, Span::call_site()).to_tokens(tokens)
+    }
+}
+
+
+/// The number of columns that the text of a literal occupies in a terminal.
+pub struct SgrWidth(usize);
+
+impl Parse for SgrWidth {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let SgrStrip(text) = input.parse()?;
+        Ok(Self(text.width()))
+    }
+}
+
+impl ToTokens for SgrWidth {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        Literal::usize_suffixed(self.0).to_tokens(tokens)
+    }
+}
+
+
+/// The text of a literal, converted to HTML.
+pub struct SgrHtml(String);
+
+impl Parse for SgrHtml {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let markup = if input.peek(syn::Ident) && input.peek2(Token![;]) {
+            let option: syn::Ident = input.parse()?;
+            let _: Token![;] = input.parse()?;
+
+            match option.to_string().as_str() {
+                "inline" => Markup::Inline,
+                "classes" => Markup::Classes,
+                other => return Err(syn::Error::new(
+                    option.span(),
+                    format!("unknown HTML markup `{other}`; expected `inline` \
+                    or `classes`"),
+                )),
+            }
+        } else {
+            Markup::Inline
+        };
+
+        let expr: syn::Expr = input.parse()?;
+        Ok(Self(sgr_runtime::html(&literal(&expr)?, markup)))
+    }
+}
+
+impl ToTokens for SgrHtml {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        syn::LitStr::new(&self.0, Span::call_site()).to_tokens(tokens)
+    }
+}
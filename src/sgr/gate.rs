@@ -0,0 +1,173 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use sgr_runtime::{escape_len, Stream};
+use syn::parse::Parser;
+use super::{
+    nested::{concat, gradient, hyperlink, invocation, Args, FormatArg, Items},
+    Output,
+};
+
+
+/// The stream whose color support decides the output of a gated invocation.
+///     This is standard output, unless the invocation prints to standard
+///     error.
+pub fn stream(output: &Output) -> TokenStream {
+    match output {
+        Output::Print { stream: Stream::Stderr, .. } => {
+            quote!(::sgr_runtime::Stream::Stderr)
+        }
+        _ => quote!(::sgr_runtime::Stream::Stdout),
+    }
+}
+
+
+/// Split a string into its plain text and its control sequences. Each run of
+///     consecutive sequences is passed to a closure, and replaced with its
+///     return value.
+pub fn replace_escapes(
+    text: &str,
+    mut f: impl FnMut(&str) -> String,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < text.len() {
+        let start = i;
+
+        while let Some(len) = escape_len(&text[i..]) {
+            i += len;
+        }
+
+        if start < i {
+            out.push_str(&f(&text[start..i]));
+        } else {
+            let ch = text[i..].chars().next().unwrap();
+            out.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+
+    out
+}
+
+
+/// Remove every CSI sequence and operating system command from a string.
+pub fn strip(text: &str) -> String {
+    replace_escapes(text, |_| String::new())
+}
+
+
+/// Rewrite the contents of a Literal Mode invocation, so that its output has
+///     no control sequences, from nested invocations or otherwise.
+pub fn plain_concat(contents: &TokenStream) -> TokenStream {
+    fn plain_item(expr: &syn::Expr) -> TokenStream {
+        if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = expr
+        {
+            syn::LitStr::new(&strip(&s.value()), s.span()).into_token_stream()
+        } else if let Some(sgr) = invocation(expr) {
+            let plain = plain_concat(&sgr.base().contents);
+            quote!(concat!(#plain))
+        } else if let Some(link) = hyperlink(expr) {
+            let plain = plain_concat(&link.base().contents);
+            quote!(concat!(#plain))
+        } else if let Some(gradient) = gradient(expr) {
+            gradient.plain().into_token_stream()
+        } else if let Some(m) = concat(expr) {
+            let plain = plain_concat(&m.mac.tokens);
+            quote!(concat!(#plain))
+        } else {
+            expr.to_token_stream()
+        }
+    }
+
+    match Items::parse_terminated.parse2(contents.clone()) {
+        Ok(items) => {
+            let items = items.iter().map(plain_item);
+            quote!(#(#items),*)
+        }
+        Err(..) => contents.clone(),
+    }
+}
+
+
+/// Rewrite an argument of a gated template, so that any nested invocation in
+///     it is also gated, on the same stream as the template.
+pub fn gate_arg(expr: syn::Expr, output: &Output) -> syn::Expr {
+    let stream = stream(output);
+
+    if let Some(sgr) = invocation(&expr) {
+        match sgr.base().behavior.output {
+            Output::Concat => {
+                let colored = sgr.expand(false);
+                let plain = plain_concat(&sgr.base().contents);
+
+                syn::parse_quote!(
+                    ::sgr_runtime::Choice(#colored, concat!(#plain), #stream)
+                )
+            }
+            _ => syn::parse2(sgr.expand(true)).unwrap_or(expr),
+        }
+    } else if let Some(m) = concat(&expr) {
+        let plain = plain_concat(&m.mac.tokens);
+        syn::parse_quote!(
+            ::sgr_runtime::Choice(#expr, concat!(#plain), #stream)
+        )
+    } else if let Some(gradient) = gradient(&expr) {
+        let plain = gradient.plain();
+        syn::parse_quote!(::sgr_runtime::Choice(#expr, #plain, #stream))
+    } else {
+        expr
+    }
+}
+
+
+/// Resolve a finished template and its arguments into an expression whose
+///     control sequences are only written if color is enabled at runtime.
+///
+/// In Literal Mode, the output is a pair of literals, colored and plain.
+///     Otherwise, each run of control sequences in the template is replaced
+///     with a placeholder, for a value that checks at runtime whether to write
+///     them.
+pub fn expand(
+    output: &Output,
+    template: &syn::LitStr,
+    contents: &TokenStream,
+) -> TokenStream {
+    let stream = stream(output);
+    let text = template.value();
+
+    if matches!(output, Output::Concat) {
+        let plain = syn::LitStr::new(&strip(&text), template.span());
+        return quote!((#template, #plain));
+    }
+
+    let parsed = Args::parse_terminated.parse2(contents.clone());
+    let mut args: Args = match parsed {
+        Ok(args) => args.into_iter()
+            .map(|arg| FormatArg {
+                name: arg.name,
+                expr: gate_arg(arg.expr, output),
+            })
+            .collect(),
+        Err(..) => return output.expand(template, contents),
+    };
+
+    let mut escapes: Vec<String> = Vec::new();
+    let gated = replace_escapes(&text, |seq| {
+        let name = format!("__sgr_{}", escapes.len());
+        escapes.push(seq.replace("{{", "{").replace("}}", "}"));
+        format!("{{{name}}}")
+    });
+
+    for (i, seq) in escapes.iter().enumerate() {
+        let lit = syn::LitStr::new(seq, Span::call_site());
+
+        args.push(FormatArg {
+            name: Some(format_ident!("__sgr_{}", i)),
+            expr: syn::parse_quote!(::sgr_runtime::Escape(#lit, #stream)),
+        });
+    }
+
+    let gated = syn::LitStr::new(&gated, template.span());
+    output.expand(&gated, &args.into_token_stream())
+}
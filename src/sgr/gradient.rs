@@ -0,0 +1,183 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{parse::{Parse, ParseStream}, Token};
+use unicode_segmentation::UnicodeSegmentation;
+use super::{
+    fmt_color_end,
+    fmt_rgb,
+    minimize::minimize,
+    rgb::{Blend, Rgb},
+    Behavior,
+    Output,
+    Palette,
+    Revert,
+    BG,
+    FG,
+};
+
+
+/// Text colored by a gradient between two or more colors, one grapheme cluster
+///     at a time. Every color is found at compile-time, so only Literal Mode is
+///     supported.
+pub struct SgrGradient {
+    stops: Vec<Rgb>,
+    background: bool,
+    blend: Blend,
+    palette: Palette,
+    behavior: Behavior,
+    text: syn::LitStr,
+}
+
+impl SgrGradient {
+    /// Find the color at some fraction of the way along the gradient. The
+    ///     stops are evenly spaced.
+    fn color_at(&self, t: f64) -> Rgb {
+        let last = self.stops.len() - 1;
+        let pos = t * last as f64;
+        let i = (pos.floor() as usize).min(last - 1);
+
+        self.stops[i].mix(&self.stops[i + 1], pos - i as f64, self.blend)
+    }
+
+    fn fmt_opening(&self, rgb: &Rgb) -> String {
+        if self.background {
+            fmt_rgb::<BG>(rgb, self.palette)
+        } else {
+            fmt_rgb::<FG>(rgb, self.palette)
+        }
+    }
+
+    fn fmt_closing(&self) -> String {
+        if self.background {
+            fmt_color_end::<BG>()
+        } else {
+            fmt_color_end::<FG>()
+        }
+    }
+
+    /// Check whether a grapheme cluster is colored. The color of whitespace is
+    ///     only visible in the background.
+    fn colors(&self, cluster: &str) -> bool {
+        self.background || !cluster.trim().is_empty()
+    }
+
+    /// Find every SGR parameter that may be reset by this gradient.
+    pub fn disturbed(&self) -> Vec<String> {
+        if !self.text.value().graphemes(true).any(|c| self.colors(c)) {
+            return Vec::new();
+        }
+
+        match self.behavior.revert {
+            Revert::One => vec![self.fmt_closing()],
+            Revert::All => vec![String::new()],
+            Revert::None => Vec::new(),
+        }
+    }
+
+    /// The text of this gradient, without any color.
+    pub fn plain(&self) -> &syn::LitStr {
+        &self.text
+    }
+
+    /// Color every grapheme cluster of the text.
+    fn render(&self) -> String {
+        let text = self.text.value();
+        let clusters: Vec<&str> = text.graphemes(true).collect();
+        let steps = clusters.len().saturating_sub(1).max(1) as f64;
+        let mut out = String::with_capacity(text.len() * 20);
+        let mut opened = false;
+
+        for (i, cluster) in clusters.iter().enumerate() {
+            if self.colors(cluster) {
+                let rgb = self.color_at(i as f64 / steps);
+                out.push_str(&format!(sgr!("{}"), self.fmt_opening(&rgb)));
+                opened = true;
+            }
+
+            out.push_str(cluster);
+        }
+
+        //  Nothing is closed if nothing was opened.
+        if !opened {
+            return out;
+        }
+
+        match self.behavior.revert {
+            Revert::One => {
+                out.push_str(&format!(sgr!("{}"), self.fmt_closing()));
+            }
+            Revert::All => out.push_str(sgr!()),
+            Revert::None => {}
+        }
+
+        minimize(&out)
+    }
+}
+
+impl Parse for SgrGradient {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut stops: Vec<Rgb> = vec![input.parse()?];
+
+        while input.parse::<Token![->]>().is_ok() {
+            stops.push(input.parse()?);
+        }
+
+        if stops.len() < 2 {
+            return Err(input.error(
+                "expected `->` and another color; a gradient needs at least \
+                two colors",
+            ));
+        }
+
+        let mut background = false;
+        let mut blend = Blend::default();
+        let mut palette = Palette::Rgb;
+
+        while input.parse::<Token![,]>().is_ok() {
+            let option: syn::Ident = input.parse()?;
+
+            match option.to_string().as_str() {
+                "fg" => background = false,
+                "bg" => background = true,
+                "linear" => blend = Blend::Linear,
+                "oklab" => blend = Blend::Oklab,
+                "color_256" => palette = Palette::Color256,
+                "color_16" => palette = Palette::Color16,
+                other => return Err(syn::Error::new(
+                    option.span(),
+                    format!("unknown gradient option `{other}`; expected \
+                    `fg`, `bg`, `linear`, `oklab`, `color_256`, or \
+                    `color_16`"),
+                )),
+            }
+        }
+
+        let _: Token![;] = input.parse()?;
+        let behavior: Behavior = input.parse()?;
+
+        if !matches!(behavior.output, Output::Concat) {
+            return Err(input.error(
+                "a gradient is computed at compile-time, and only supports \
+                Literal Mode",
+            ));
+        }
+
+        let text: syn::LitStr = input.parse()?;
+        let _ = input.parse::<Token![,]>();
+
+        Ok(Self { stops, background, blend, palette, behavior, text })
+    }
+}
+
+impl ToTokens for SgrGradient {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let colored = syn::LitStr::new(&self.render(), Span::call_site());
+
+        tokens.extend(if self.behavior.gated {
+            let plain = &self.text;
+            quote!((#colored, #plain))
+        } else {
+            colored.into_token_stream()
+        });
+    }
+}
@@ -0,0 +1,259 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse::{Parse, ParseStream, Parser}, Token};
+use super::{
+    gate,
+    nested::{Args, FormatArg},
+    pad,
+    Output,
+    Revert,
+    SgrBase,
+};
+
+
+/// Terminates an operating system command.
+const ST: &str = "\x1B\\";
+/// Ends a hyperlink, with empty parameters and an empty URI.
+const CLOSE: &str = "\x1B]8;;\x1B\\";
+
+
+fn validate_url(url: &str) -> Result<(), String> {
+    if let Some(c) = url.chars().find(|c| !('!'..='~').contains(c)) {
+        return Err(format!(
+            "URL contains the character {c:?}, which must be percent-encoded",
+        ));
+    }
+
+    let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(String::from(
+            "URL must be absolute, beginning with a scheme such as `https:`",
+        ))
+    }
+}
+
+
+fn validate_id(id: &str) -> Result<(), String> {
+    if id.is_empty() {
+        Err(String::from("hyperlink ID cannot be empty"))
+    } else if let Some(c) = id.chars()
+        .find(|c| !('!'..='~').contains(c) || *c == ':' || *c == ';')
+    {
+        Err(format!("hyperlink ID cannot contain the character {c:?}"))
+    } else {
+        Ok(())
+    }
+}
+
+
+/// A parameter of a hyperlink, which is validated at compile-time if it is a
+///     string literal.
+enum Param {
+    Literal(String),
+    Runtime(Box<syn::Expr>),
+}
+
+impl Param {
+    fn parse(
+        input: ParseStream,
+        validate: fn(&str) -> Result<(), String>,
+    ) -> syn::Result<Self> {
+        match input.parse::<syn::Expr>()? {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => {
+                let value = s.value();
+
+                match validate(&value) {
+                    Ok(()) => Ok(Self::Literal(value)),
+                    Err(msg) => Err(syn::Error::new(s.span(), msg)),
+                }
+            }
+            expr => Ok(Self::Runtime(Box::new(expr))),
+        }
+    }
+
+    /// Write this parameter into a template, either directly or as a
+    ///     placeholder for a named argument.
+    fn fmt_template(&self, name: &str, args: &mut Args) -> String {
+        match self {
+            Self::Literal(value) => value.replace('{', "{{").replace('}', "}}"),
+            Self::Runtime(expr) => {
+                args.push(FormatArg {
+                    name: Some(format_ident!("{}", name)),
+                    expr: *expr.clone(),
+                });
+
+                format!("{{{name}}}")
+            }
+        }
+    }
+}
+
+
+/// Text that links to a URL, using the OSC 8 control sequence.
+pub struct Hyperlink {
+    url: Param,
+    id: Option<Param>,
+    base: SgrBase,
+}
+
+impl Hyperlink {
+    pub fn base(&self) -> &SgrBase { &self.base }
+
+    /// Find every SGR parameter that may be reset by the output of this
+    ///     invocation. A hyperlink has no SGR parameters of its own, but may
+    ///     contain nested invocations that do.
+    pub fn disturbed(&self) -> Vec<String> {
+        let mut params = self.base.disturbed();
+
+        if self.base.behavior.revert == Revert::All {
+            params.push(String::new());
+        }
+
+        params
+    }
+
+    /// Format the opening sequence, for a link that is entirely literal.
+    fn fmt_opening(&self) -> Option<String> {
+        let url = match &self.url {
+            Param::Literal(url) => url,
+            Param::Runtime(..) => return None,
+        };
+
+        let id = match &self.id {
+            None => String::new(),
+            Some(Param::Literal(id)) => format!("id={id}"),
+            Some(Param::Runtime(..)) => return None,
+        };
+
+        Some(format!("\x1B]8;{id};{url}{ST}"))
+    }
+
+    fn fmt_closing(&self) -> String {
+        match self.base.behavior.revert {
+            Revert::One => String::from(CLOSE),
+            Revert::All => format!("{CLOSE}{}", sgr!()),
+            Revert::None => String::new(),
+        }
+    }
+}
+
+impl Parse for Hyperlink {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let url = Param::parse(input, validate_url)?;
+
+        let id = if input.parse::<Token![,]>().is_ok() {
+            let key: syn::Ident = input.parse()?;
+
+            if key != "id" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("unknown hyperlink parameter `{key}`; \
+                    expected `id`"),
+                ));
+            }
+
+            let _: Token![=] = input.parse()?;
+            Some(Param::parse(input, validate_id)?)
+        } else {
+            None
+        };
+
+        let _: Token![;] = input.parse()?;
+        let base: SgrBase = input.parse()?;
+
+        if matches!(base.behavior.output, Output::Concat) {
+            for param in [Some(&url), id.as_ref()].into_iter().flatten() {
+                if let Param::Runtime(expr) = param {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "hyperlink parameters that are not string literals \
+                        require an output mode sigil such as `%` or `@`",
+                    ));
+                }
+            }
+        }
+
+        Ok(Self { url, id, base })
+    }
+}
+
+impl ToTokens for Hyperlink {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let behavior = &self.base.behavior;
+        let contents = &self.base.contents;
+        let closing = self.fmt_closing();
+
+        let template = match &self.base.template {
+            Some(template) => template,
+            None => {
+                //  Literal Mode, in which every parameter must be a literal.
+                let opening = self.fmt_opening().unwrap_or_default();
+                let colored = quote!(concat!(#opening, #contents, #closing));
+
+                tokens.extend(if behavior.gated {
+                    let plain = gate::plain_concat(contents);
+                    quote!((#colored, concat!(#plain)))
+                } else {
+                    colored
+                });
+
+                return;
+            }
+        };
+
+        let (template, contents) = pad::pad(template, contents, behavior);
+        let parsed = Args::parse_terminated.parse2(contents);
+        let mut args: Args = match parsed {
+            Ok(args) => args,
+            Err(err) => return tokens.extend(err.to_compile_error()),
+        };
+
+        //  Runtime parameters are given to the template as named arguments.
+        let mut params = Args::new();
+        let id = match &self.id {
+            Some(id) => {
+                format!("id={}", id.fmt_template("__link_id", &mut params))
+            }
+            None => String::new(),
+        };
+        let url = self.url.fmt_template("__link_url", &mut params);
+        let opening = format!("\x1B]8;{id};{url}{ST}");
+
+        let opening = if params.is_empty() {
+            opening
+        } else if behavior.gated {
+            //  The opening sequence cannot be separated from its parameters,
+            //      so it must be gated as a whole.
+            let stream = gate::stream(&behavior.output);
+
+            args.push(FormatArg {
+                name: Some(format_ident!("__link")),
+                expr: syn::parse_quote!(::sgr_runtime::Escape(
+                    format_args!(#opening, #params),
+                    #stream,
+                )),
+            });
+
+            String::from("{__link}")
+        } else {
+            args.extend(params);
+            opening
+        };
+
+        let text = format!("{opening}{}{closing}", template.value());
+        let template = syn::LitStr::new(&text, template.span());
+        let args = args.into_token_stream();
+
+        tokens.extend(if behavior.gated {
+            gate::expand(&behavior.output, &template, &args)
+        } else {
+            behavior.output.expand(&template, &args)
+        });
+    }
+}
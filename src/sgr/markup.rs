@@ -0,0 +1,282 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{parse::{Parse, ParseStream}, Token};
+use super::{
+    eval,
+    find_style,
+    fmt_256,
+    fmt_color_end,
+    fmt_rgb,
+    gate,
+    minimize::minimize,
+    nested::{conflicts, disturbed_args},
+    pad,
+    rgb::Rgb,
+    template::placeholders,
+    BG,
+    Behavior,
+    FG,
+    Output,
+    Palette,
+    Revert,
+    SgrBase,
+    UL,
+};
+
+
+/// An opened markup tag.
+struct Tag {
+    name: String,
+    opening: String,
+    closing: String,
+}
+
+impl Tag {
+    fn parse(body: &str) -> Result<Self, String> {
+        let (name, value) = match body.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (body.trim(), None),
+        };
+
+        let parse_rgb = |value: &str| value.parse::<Rgb>()
+            .map_err(|err| format!("invalid RGB color `{value}`: {err}"));
+        let parse_256 = |value: &str| value.parse::<u8>()
+            .map_err(|_| format!("invalid 8-bit color index `{value}`"));
+
+        let (opening, closing) = match (name, value) {
+            ("rgb" | "color_rgb", Some(value)) => (
+                fmt_rgb::<FG>(&parse_rgb(value)?, Palette::Rgb),
+                fmt_color_end::<FG>(),
+            ),
+            ("rgb_bg" | "color_rgb_bg", Some(value)) => (
+                fmt_rgb::<BG>(&parse_rgb(value)?, Palette::Rgb),
+                fmt_color_end::<BG>(),
+            ),
+            ("256" | "color_256", Some(value)) => (
+                fmt_256::<FG>(parse_256(value)?),
+                fmt_color_end::<FG>(),
+            ),
+            ("256_bg" | "color_256_bg", Some(value)) => (
+                fmt_256::<BG>(parse_256(value)?),
+                fmt_color_end::<BG>(),
+            ),
+            ("uline_rgb" | "uline_color_rgb", Some(value)) => (
+                fmt_rgb::<UL>(&parse_rgb(value)?, Palette::Rgb),
+                fmt_color_end::<UL>(),
+            ),
+            ("uline_256" | "uline_color_256", Some(value)) => (
+                fmt_256::<UL>(parse_256(value)?),
+                fmt_color_end::<UL>(),
+            ),
+            (_, Some(_)) => {
+                return Err(format!("tag `<{name}>` does not take a value"));
+            }
+            (_, None) => {
+                let style = match name {
+                    "b" => "bold",
+                    "i" => "italic",
+                    "u" => "uline",
+                    "s" => "strike",
+                    other => other,
+                };
+
+                find_style(style)
+                    .ok_or_else(|| format!("unknown tag `<{name}>`"))?
+            }
+        };
+
+        Ok(Self { name: String::from(name), opening, closing })
+    }
+}
+
+
+/// Text with inline tags, replaced with SGR sequences at compile-time.
+pub struct SgrMarkup {
+    behavior: Behavior,
+    template: syn::LitStr,
+    contents: TokenStream,
+}
+
+impl SgrMarkup {
+    fn render(
+        text: &str,
+        behavior: &Behavior,
+        contents: &TokenStream,
+    ) -> Result<String, String> {
+        fn flush(out: &mut String, pending: &mut Vec<String>) {
+            if !pending.is_empty() {
+                out.push_str(&format!(sgr!("{}"), pending.join(";")));
+                pending.clear();
+            }
+        }
+
+        //  Without arguments, a Literal Mode template is only text.
+        let holes = match behavior.output {
+            Output::Concat if contents.is_empty() => Vec::new(),
+            _ => placeholders(text)?,
+        };
+        let args = disturbed_args(contents);
+
+        let mut out = String::with_capacity(text.len());
+        let mut pending: Vec<String> = Vec::new();
+        let mut stack: Vec<Tag> = Vec::new();
+        let mut holes = holes.into_iter().peekable();
+        let mut i = 0;
+
+        while let Some(rest) = text.get(i..).filter(|rest| !rest.is_empty()) {
+            if let Some(hole) = holes.next_if(|hole| hole.start == i) {
+                flush(&mut out, &mut pending);
+                out.push_str(&text[hole.start..hole.end]);
+                i = hole.end;
+
+                //  A nested invocation in the arguments may revert open tags.
+                if let Some((_, params)) = args.iter()
+                    .find(|(arg, _)| *arg == hole.arg)
+                {
+                    for tag in &stack {
+                        if conflicts(params, &tag.closing) {
+                            pending.push(tag.opening.clone());
+                        }
+                    }
+                }
+            } else if rest.starts_with("<<") {
+                flush(&mut out, &mut pending);
+                out.push('<');
+                i += 2;
+            } else if rest.starts_with('<') {
+                let len = rest.find('>').ok_or_else(|| format!(
+                    "unterminated tag at offset {i} \
+                    (use `<<` for a literal `<`)",
+                ))?;
+                let body = &rest[1..len];
+                i += len + 1;
+
+                if let Some(name) = body.strip_prefix('/') {
+                    let tag = stack.pop().ok_or_else(|| format!(
+                        "closing tag `</{name}>` was never opened",
+                    ))?;
+
+                    if tag.name != name.trim() {
+                        return Err(format!(
+                            "mismatched closing tag `</{name}>`; \
+                            expected `</{}>`",
+                            tag.name,
+                        ));
+                    }
+
+                    //  Closing a tag may also revert the tags enclosing it.
+                    let params: Vec<String> = tag.closing.split(';')
+                        .map(String::from)
+                        .collect();
+
+                    pending.push(tag.closing);
+
+                    for outer in &stack {
+                        if conflicts(&params, &outer.closing) {
+                            pending.push(outer.opening.clone());
+                        }
+                    }
+                } else {
+                    let tag = Tag::parse(body)?;
+                    pending.push(tag.opening.clone());
+                    stack.push(tag);
+                }
+            } else {
+                let ch = rest.chars().next().unwrap();
+
+                flush(&mut out, &mut pending);
+                out.push(ch);
+                i += ch.len_utf8();
+            }
+        }
+
+        match behavior.revert {
+            Revert::One => {
+                if let Some(tag) = stack.last() {
+                    return Err(format!("unclosed tag `<{}>`", tag.name));
+                }
+
+                flush(&mut out, &mut pending);
+            }
+            Revert::All => out.push_str(sgr!()),
+            Revert::None => flush(&mut out, &mut pending),
+        }
+
+        Ok(out)
+    }
+}
+
+impl Parse for SgrMarkup {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let behavior: Behavior = input.parse()?;
+        let template: syn::LitStr = input.parse()?;
+        let mut contents = TokenStream::new();
+
+        if input.parse::<Token![,]>().is_ok() {
+            while let Ok(token) = input.parse::<TokenTree>() {
+                contents.append(token);
+            }
+        }
+
+        let rendered = Self::render(&template.value(), &behavior, &contents)
+            .map_err(|msg| syn::Error::new(template.span(), msg))?;
+        let template = syn::LitStr::new(&rendered, template.span());
+
+        //  In Literal Mode, the output must be a single literal, so the
+        //      template is filled in now, and concatenated instead.
+        let contents = match behavior.output {
+            Output::Concat if !contents.is_empty() => {
+                SgrBase::fill_template(&template, &contents, &behavior)?
+            }
+            _ => contents,
+        };
+
+        Ok(Self { behavior, template, contents })
+    }
+}
+
+impl ToTokens for SgrMarkup {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let output = &self.behavior.output;
+
+        if let Output::Concat = output {
+            let items = if self.contents.is_empty() {
+                self.template.to_token_stream()
+            } else {
+                self.contents.clone()
+            };
+            let colored = quote!(concat!(#items));
+
+            //  Every part of the output is known, so the sequences in it can
+            //      be merged, and those that do nothing removed.
+            let colored = match syn::parse2(colored.clone())
+                .and_then(|expr| eval::literal(&expr))
+            {
+                Ok(text) => {
+                    let text = minimize(&text);
+                    syn::LitStr::new(&text, Span::call_site())
+                        .into_token_stream()
+                }
+                Err(..) => colored,
+            };
+
+            if self.behavior.gated {
+                let plain = gate::plain_concat(&items);
+                tokens.extend(quote!((#colored, concat!(#plain))));
+            } else {
+                tokens.extend(colored);
+            }
+
+            return;
+        }
+
+        let (template, contents) =
+            pad::pad(&self.template, &self.contents, &self.behavior);
+
+        if self.behavior.gated {
+            tokens.extend(gate::expand(output, &template, &contents));
+        } else {
+            tokens.extend(output.expand(&template, &contents));
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use sgr_runtime::escape_len;
+use super::gate::replace_escapes;
+
+
+/// A part of the rendition state that an SGR parameter may change.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+enum Slot {
+    Bold,
+    Faint,
+    Italic,
+    Underline,
+    Blink,
+    Invert,
+    Hidden,
+    Strike,
+    Overline,
+    Fg,
+    Bg,
+    Ul,
+}
+
+impl Slot {
+    const ALL: [(Self, &'static str); 12] = [
+        (Self::Bold, "22"),
+        (Self::Faint, "22"),
+        (Self::Italic, "23"),
+        (Self::Underline, "24"),
+        (Self::Blink, "25"),
+        (Self::Invert, "27"),
+        (Self::Hidden, "28"),
+        (Self::Strike, "29"),
+        (Self::Overline, "55"),
+        (Self::Fg, "39"),
+        (Self::Bg, "49"),
+        (Self::Ul, "59"),
+    ];
+}
+
+
+/// What one SGR parameter does.
+enum Effect {
+    /// Return every part of the state to its default, including any that this
+    ///     module does not know about.
+    Reset,
+    /// Set some parts of the state to the value of this parameter.
+    Set(Vec<Slot>),
+    /// Do something that this module does not know about.
+    Unknown,
+}
+
+
+/// One SGR parameter. An extended color is one parameter, even though its
+///     parts are separated by semicolons.
+struct Param {
+    text: String,
+    effect: Effect,
+}
+
+impl Param {
+    fn new(text: String) -> Self {
+        use Slot::*;
+
+        let code = match text.split(';').next().unwrap_or_default() {
+            "" => 0,
+            code => match code.parse::<u16>() {
+                Ok(code) => code,
+                Err(..) => return Self { text, effect: Effect::Unknown },
+            },
+        };
+
+        let slots = match code {
+            0 => return Self { text, effect: Effect::Reset },
+            1 => vec![Bold],
+            2 => vec![Faint],
+            22 => vec![Bold, Faint],
+            3 | 23 => vec![Italic],
+            4 | 24 => vec![Underline],
+            5 | 6 | 25 => vec![Blink],
+            7 | 27 => vec![Invert],
+            8 | 28 => vec![Hidden],
+            9 | 29 => vec![Strike],
+            53 | 55 => vec![Overline],
+            30..=39 | 90..=97 => vec![Fg],
+            40..=49 | 100..=107 => vec![Bg],
+            58 | 59 => vec![Ul],
+            _ => return Self { text, effect: Effect::Unknown },
+        };
+
+        Self { text, effect: Effect::Set(slots) }
+    }
+
+    /// Read the parameters of an SGR sequence, if it is one that this module
+    ///     understands.
+    fn parse_seq(seq: &str) -> Option<Vec<Self>> {
+        let body = seq.strip_prefix("\x1B[")?.strip_suffix('m')?;
+
+        if !body.bytes().all(|b| b.is_ascii_digit() || b == b';') {
+            return None;
+        }
+
+        let mut parts = body.split(';');
+        let mut params = Vec::new();
+
+        while let Some(part) = parts.next() {
+            //  With semicolons, an extended color takes the parameters after
+            //      it: `5;n` for an indexed color, or `2;r;g;b` for RGB.
+            let extra = match (part, parts.clone().next()) {
+                ("38" | "48" | "58", Some("5")) => 2,
+                ("38" | "48" | "58", Some("2")) => 4,
+                ("38" | "48" | "58", _) => return None,
+                _ => 0,
+            };
+
+            let mut text = String::from(part);
+
+            for _ in 0..extra {
+                text.push(';');
+                text.push_str(parts.next()?);
+            }
+
+            params.push(Self::new(text));
+        }
+
+        Some(params)
+    }
+}
+
+
+/// The parts of the rendition state that are known at some point in a string.
+#[derive(Default)]
+struct State {
+    known: HashMap<Slot, String>,
+    /// Whether the state has been reset, and has not changed since.
+    clean: bool,
+}
+
+impl State {
+    /// Apply a parameter to the state, and return whether it changed anything.
+    fn apply(&mut self, param: &Param) -> bool {
+        match &param.effect {
+            Effect::Reset if self.clean => false,
+            Effect::Reset => {
+                self.known = Slot::ALL.iter()
+                    .map(|(slot, value)| (*slot, String::from(*value)))
+                    .collect();
+                self.clean = true;
+                true
+            }
+            Effect::Set(slots) => {
+                //  ECMA-48 makes bold and faint values of one intensity, but
+                //      other terminals may show them together. Either one is
+                //      recorded in both parts, so that neither is ever taken
+                //      to be already set after the other.
+                let slots = match slots.as_slice() {
+                    [Slot::Bold] | [Slot::Faint] => &[Slot::Bold, Slot::Faint],
+                    slots => slots,
+                };
+                let mut changed = false;
+
+                for slot in slots {
+                    let old = self.known.insert(*slot, param.text.clone());
+                    changed |= old.as_ref() != Some(&param.text);
+                }
+
+                self.clean &= !changed;
+                changed
+            }
+            Effect::Unknown => {
+                self.known.clear();
+                self.clean = false;
+                true
+            }
+        }
+    }
+}
+
+
+/// Merge consecutive SGR sequences, and remove every parameter that does not
+///     change how any text is rendered: one that is overridden before any text
+///     follows it, or one that sets a value that is already set.
+///
+/// The state before the string is not known, so the first sequence to set
+///     each part of it is always kept. Sequences that this module does not
+///     understand are kept as they are, and are treated as text.
+pub fn minimize(text: &str) -> String {
+    let mut state = State::default();
+
+    replace_escapes(text, |run| {
+        let mut out = String::with_capacity(run.len());
+        let mut params: Vec<Param> = Vec::new();
+        let mut i = 0;
+
+        while i < run.len() {
+            let len = escape_len(&run[i..]).unwrap_or(run.len() - i);
+            let seq = &run[i..i + len];
+            i += len;
+
+            match Param::parse_seq(seq) {
+                Some(seq) => params.extend(seq),
+                None => {
+                    out.push_str(&flush(&mut params, &mut state));
+                    out.push_str(seq);
+
+                    //  Whatever is not understood may have changed the state.
+                    if seq.ends_with('m') {
+                        state = State::default();
+                    }
+                }
+            }
+        }
+
+        out.push_str(&flush(&mut params, &mut state));
+        out
+    })
+}
+
+
+/// Write the parameters of one merged SGR sequence, without those that have
+///     no effect.
+fn flush(params: &mut Vec<Param>, state: &mut State) -> String {
+    //  A parameter followed by others that set all of the same parts of the
+    //      state, with no text in between, is never seen.
+    let mut later: Vec<Slot> = Vec::new();
+    let mut reset = false;
+    let mut kept: Vec<Param> = Vec::new();
+
+    for param in params.drain(..).rev() {
+        let hidden = reset || match &param.effect {
+            Effect::Set(slots) => slots.iter().all(|s| later.contains(s)),
+            _ => false,
+        };
+
+        match &param.effect {
+            Effect::Reset => reset = true,
+            Effect::Set(slots) => later.extend(slots),
+            Effect::Unknown => {}
+        }
+
+        if !hidden {
+            kept.push(param);
+        }
+    }
+
+    kept.reverse();
+    kept.retain(|param| state.apply(param));
+
+    match kept.as_slice() {
+        [] => String::new(),
+        [param] => format!(sgr!("{}"), param.text),
+        _ => {
+            let texts: Vec<&str> = kept.iter()
+                .map(|p| if p.text.is_empty() { "0" } else { &p.text })
+                .collect();
+            format!(sgr!("{}"), texts.join(";"))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(
+            minimize("\x1B[1m\x1B[32mx\x1B[39m\x1B[22m"),
+            "\x1B[1;32mx\x1B[39;22m",
+        );
+        assert_eq!(
+            minimize("\x1B[38;5;173m\x1B[48;2;1;2;3mx"),
+            "\x1B[38;5;173;48;2;1;2;3mx",
+        );
+        assert_eq!(minimize("\x1B[m\x1B[1mx"), "\x1B[0;1mx");
+        assert_eq!(minimize("\x1B[4m\x1B[58;5;1mx"), "\x1B[4;58;5;1mx");
+    }
+
+    #[test]
+    fn test_overridden() {
+        //  A nested color is restored to the outer color.
+        assert_eq!(
+            minimize("\x1B[31ma \x1B[32mb\x1B[39m\x1B[31m c\x1B[39m"),
+            "\x1B[31ma \x1B[32mb\x1B[31m c\x1B[39m",
+        );
+
+        //  A style that is opened and closed without text only closes.
+        assert_eq!(minimize("a\x1B[31m\x1B[39mb"), "a\x1B[39mb");
+
+        //  Everything before a reset is overridden by it.
+        assert_eq!(minimize("x\x1B[1;31m\x1B[m"), "x\x1B[m");
+        assert_eq!(minimize("x\x1B[m\x1B[1m\x1B[m"), "x\x1B[m");
+
+        //  Bold and faint are closed together, but opened separately.
+        assert_eq!(minimize("\x1B[22m\x1B[1mx"), "\x1B[22;1mx");
+        assert_eq!(minimize("\x1B[1m\x1B[22mx"), "\x1B[22mx");
+        assert_eq!(minimize("\x1B[22m\x1B[1;2mx"), "\x1B[1;2mx");
+    }
+
+    #[test]
+    fn test_redundant() {
+        assert_eq!(minimize("\x1B[31ma\x1B[31mb"), "\x1B[31mab");
+        assert_eq!(minimize("\x1B[ma\x1B[39;49mb\x1B[m"), "\x1B[mab");
+        assert_eq!(
+            minimize("\x1B[1ma\x1B[22mb\x1B[22mc"),
+            "\x1B[1ma\x1B[22mbc",
+        );
+
+        //  Bold and faint are not redundant after each other.
+        assert_eq!(
+            minimize("\x1B[1ma\x1B[2mb\x1B[1mc"),
+            "\x1B[1ma\x1B[2mb\x1B[1mc",
+        );
+        assert_eq!(
+            minimize("\x1B[1;2ma\x1B[2mb\x1B[1mc"),
+            "\x1B[1;2mab\x1B[1mc",
+        );
+
+        //  The state before the string is not known.
+        assert_eq!(minimize("\x1B[39ma"), "\x1B[39ma");
+        assert_eq!(minimize("\x1B[ma"), "\x1B[ma");
+    }
+
+    #[test]
+    fn test_unknown() {
+        //  Other sequences are kept, and separate SGR sequences.
+        assert_eq!(
+            minimize("\x1B[31m\x1B]8;;u\x1B\\\x1B[32mx"),
+            "\x1B[31m\x1B]8;;u\x1B\\\x1B[32mx",
+        );
+        assert_eq!(
+            minimize("\x1B[41m\x1B[2K\x1B[49mx"),
+            "\x1B[41m\x1B[2K\x1B[49mx",
+        );
+
+        //  Unknown parameters are kept, and forget what is known.
+        assert_eq!(
+            minimize("\x1B[31m\x1B[10ma\x1B[31mb"),
+            "\x1B[31;10ma\x1B[31mb",
+        );
+        assert_eq!(
+            minimize("\x1B[31ma\x1B[38;7mb\x1B[31m"),
+            "\x1B[31ma\x1B[38;7mb\x1B[31m",
+        );
+
+        //  Parameters with subparameters, such as curly underlines, are not
+        //      understood.
+        assert_eq!(
+            minimize("\x1B[4:3m\x1B[24mx\x1B[4:3m"),
+            "\x1B[4:3m\x1B[24mx\x1B[4:3m",
+        );
+        assert_eq!(minimize("plain \x1B text"), "plain \x1B text");
+    }
+}
@@ -0,0 +1,305 @@
+use proc_macro2::{Span, TokenStream};
+use quote::ToTokens;
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    Token,
+};
+use super::{
+    template::{placeholders, ArgRef},
+    BG,
+    FG,
+    Hyperlink,
+    Output,
+    SgrGradient,
+    Sgr256,
+    SgrBase,
+    SgrComposite,
+    SgrData,
+    SgrRgb,
+    UL,
+};
+
+
+pub type Items = Punctuated<syn::Expr, Token![,]>;
+pub type Args = Punctuated<FormatArg, Token![,]>;
+
+
+/// A single argument to a formatting macro, which may be named.
+pub struct FormatArg {
+    pub name: Option<syn::Ident>,
+    pub expr: syn::Expr,
+}
+
+impl Parse for FormatArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let named = input.peek(syn::Ident)
+            && input.peek2(Token![=])
+            && !input.peek2(Token![==]);
+
+        let name = if named {
+            let name: syn::Ident = input.parse()?;
+            let _: Token![=] = input.parse()?;
+            Some(name)
+        } else {
+            None
+        };
+
+        Ok(Self { name, expr: input.parse()? })
+    }
+}
+
+impl ToTokens for FormatArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some(name) = &self.name {
+            name.to_tokens(tokens);
+            <Token![=]>::default().to_tokens(tokens);
+        }
+
+        self.expr.to_tokens(tokens);
+    }
+}
+
+
+/// Determine the name of a macro, if it may belong to this crate.
+pub fn macro_name(mac: &syn::Macro) -> Option<String> {
+    let segments: Vec<String> = mac.path.segments.iter()
+        .map(|seg| seg.ident.to_string())
+        .collect();
+
+    match segments.as_slice() {
+        [name] if mac.path.leading_colon.is_none() => Some(name.clone()),
+        [krate, name] if krate == "sgr_macros" => Some(name.clone()),
+        _ => None,
+    }
+}
+
+
+/// Parse the input of a macro from this crate, by name.
+fn parse_invocation(
+    name: &str,
+    input: TokenStream,
+) -> Option<Box<dyn SgrData>> {
+    fn boxed<T: SgrData + Parse + 'static>(
+        input: TokenStream,
+    ) -> Option<Box<dyn SgrData>> {
+        Some(Box::new(syn::parse2::<T>(input).ok()?))
+    }
+
+    match name {
+        "sgr" => boxed::<SgrComposite>(input),
+        "color_256" => boxed::<Sgr256<FG>>(input),
+        "color_256_bg" => boxed::<Sgr256<BG>>(input),
+        "color_rgb" => boxed::<SgrRgb<FG>>(input),
+        "color_rgb_bg" => boxed::<SgrRgb<BG>>(input),
+        "uline_color_256" => boxed::<Sgr256<UL>>(input),
+        "uline_color_rgb" => boxed::<SgrRgb<UL>>(input),
+        _ => {
+            let (start, end) = crate::sgr_style(name)?;
+            let base: SgrBase = syn::parse2(input).ok()?;
+
+            Some(Box::new(base.into_format(start, end)))
+        }
+    }
+}
+
+
+/// If an expression is an invocation of an SGR macro from this crate, parse
+///     its input.
+pub fn invocation(expr: &syn::Expr) -> Option<Box<dyn SgrData>> {
+    match expr {
+        syn::Expr::Macro(m) => {
+            let name = macro_name(&m.mac)?;
+            parse_invocation(&name, m.mac.tokens.clone())
+        }
+        _ => None,
+    }
+}
+
+
+/// If an expression is an invocation of the hyperlink macro, parse its input.
+pub fn hyperlink(expr: &syn::Expr) -> Option<Hyperlink> {
+    match expr {
+        syn::Expr::Macro(m) if macro_name(&m.mac)? == "hyperlink" => {
+            syn::parse2(m.mac.tokens.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
+
+/// If an expression is an invocation of the gradient macro, parse its input.
+pub fn gradient(expr: &syn::Expr) -> Option<SgrGradient> {
+    match expr {
+        syn::Expr::Macro(m) if macro_name(&m.mac)? == "sgr_gradient" => {
+            syn::parse2(m.mac.tokens.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
+
+/// If an expression is an invocation of [`concat!`], return a copy of it.
+pub fn concat(expr: &syn::Expr) -> Option<syn::ExprMacro> {
+    match expr {
+        syn::Expr::Macro(m) => {
+            let path = &m.mac.path;
+            let is_concat = path.segments.last()?.ident == "concat"
+                && path.segments.iter().rev().skip(1)
+                .all(|seg| seg.ident == "std" || seg.ident == "core");
+
+            is_concat.then(|| m.clone())
+        }
+        _ => None,
+    }
+}
+
+
+/// Find every SGR parameter that may be reset by the output of an expression.
+///     An empty parameter indicates that *all* parameters may be reset.
+pub fn disturbed(expr: &syn::Expr) -> Vec<String> {
+    if let Some(sgr) = invocation(expr) {
+        sgr.fmt_disturbed()
+    } else if let Some(link) = hyperlink(expr) {
+        link.disturbed()
+    } else if let Some(gradient) = gradient(expr) {
+        gradient.disturbed()
+    } else if let Some(m) = concat(expr) {
+        match Items::parse_terminated.parse2(m.mac.tokens) {
+            Ok(items) => items.iter().flat_map(disturbed).collect(),
+            Err(..) => Vec::new(),
+        }
+    } else {
+        Vec::new()
+    }
+}
+
+
+/// Check whether any of a set of disturbed parameters would revert a style
+///     with the given closing parameters.
+pub fn conflicts(disturbed: &[String], closing: &str) -> bool {
+    disturbed.iter().any(|param| {
+        param.is_empty()
+            || param == "0"
+            || closing.split(';').any(|p| p == param)
+    })
+}
+
+
+impl SgrBase {
+    /// Find every SGR parameter that may be reset by the contents of this
+    ///     invocation.
+    pub fn disturbed(&self) -> Vec<String> {
+        let contents = self.contents.clone();
+
+        match self.behavior.output {
+            Output::Concat => match Items::parse_terminated.parse2(contents) {
+                Ok(items) => items.iter().flat_map(disturbed).collect(),
+                Err(..) => Vec::new(),
+            },
+            _ => match Args::parse_terminated.parse2(contents) {
+                Ok(args) => args.iter()
+                    .flat_map(|arg| disturbed(&arg.expr))
+                    .collect(),
+                Err(..) => Vec::new(),
+            },
+        }
+    }
+}
+
+
+/// Find every SGR parameter that may be reset by each argument of a template.
+pub fn disturbed_args(contents: &TokenStream) -> Vec<(ArgRef, Vec<String>)> {
+    let args = match Args::parse_terminated.parse2(contents.clone()) {
+        Ok(args) => args,
+        Err(..) => return Vec::new(),
+    };
+
+    let mut positional = 0;
+
+    args.iter().map(|arg| {
+        let arg_ref = match &arg.name {
+            Some(name) => ArgRef::Name(name.to_string()),
+            None => {
+                positional += 1;
+                ArgRef::Index(positional - 1)
+            }
+        };
+
+        (arg_ref, disturbed(&arg.expr))
+    }).collect()
+}
+
+
+fn restore_items(items: Items, reopen: &str, closing: &str) -> Items {
+    let mut restored = Items::new();
+
+    for expr in items {
+        if let Some(mut m) = concat(&expr) {
+            if let Ok(inner) = Items::parse_terminated.parse2(m.mac.tokens) {
+                m.mac.tokens = restore_items(inner, reopen, closing)
+                    .into_token_stream();
+                restored.push(syn::Expr::Macro(m));
+                continue;
+            }
+        }
+
+        let reverts = conflicts(&disturbed(&expr), closing);
+        restored.push(expr);
+
+        if reverts {
+            let lit = syn::LitStr::new(reopen, Span::call_site());
+            restored.push(syn::parse_quote!(#lit));
+        }
+    }
+
+    restored
+}
+
+
+/// Rewrite the contents of a Literal Mode invocation, so that the opening
+///     sequence is repeated after any nested invocation that would revert it.
+pub fn restore_concat(
+    contents: &TokenStream,
+    reopen: &str,
+    closing: &str,
+) -> TokenStream {
+    match Items::parse_terminated.parse2(contents.clone()) {
+        Ok(items) => restore_items(items, reopen, closing).into_token_stream(),
+        Err(..) => contents.clone(),
+    }
+}
+
+
+/// Rewrite a template, so that the opening sequence is repeated after the
+///     placeholder of any argument that would revert it.
+pub fn restore_template(
+    template: &str,
+    contents: &TokenStream,
+    reopen: &str,
+    closing: &str,
+) -> String {
+    let holes = match placeholders(template) {
+        Ok(holes) => holes,
+        Err(..) => return String::from(template),
+    };
+
+    let reverting: Vec<ArgRef> = disturbed_args(contents).into_iter()
+        .filter(|(_, params)| conflicts(params, closing))
+        .map(|(arg, _)| arg)
+        .collect();
+
+    let mut restored = String::with_capacity(template.len());
+    let mut last = 0;
+
+    for hole in holes {
+        if reverting.contains(&hole.arg) {
+            restored.push_str(&template[last..hole.end]);
+            restored.push_str(reopen);
+            last = hole.end;
+        }
+    }
+
+    restored.push_str(&template[last..]);
+    restored
+}
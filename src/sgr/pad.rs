@@ -0,0 +1,256 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::Parser;
+use unicode_width::UnicodeWidthStr;
+use super::{
+    eval,
+    gate,
+    nested::{
+        concat,
+        gradient,
+        hyperlink,
+        invocation,
+        macro_name,
+        Args,
+        FormatArg,
+    },
+    template::{placeholders, ArgRef},
+    Behavior,
+    Output,
+};
+
+
+#[derive(Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+
+/// The fill, alignment, and width of a placeholder, such as `{:-^8}`.
+struct Spec {
+    fill: char,
+    align: Align,
+    width: usize,
+}
+
+impl Spec {
+    /// Read a format spec, if it has a literal width and no parts other than a
+    ///     fill and alignment. Text is aligned to the left by default.
+    fn parse(spec: &str) -> Option<Self> {
+        let align = |c: char| match c {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        };
+
+        let mut chars = spec.chars();
+        let first = chars.next()?;
+        let second = chars.next();
+
+        let aligns = (align(first), second.and_then(align));
+        let (fill, align, width) = match aligns {
+            (_, Some(a)) => (first, a, &spec[first.len_utf8() + 1..]),
+            (Some(a), None) => (' ', a, &spec[1..]),
+            (None, None) => (' ', Align::Left, spec),
+        };
+
+        //  A leading zero is a flag, rather than part of the width.
+        let digits = width.bytes().all(|b| b.is_ascii_digit());
+        if width.starts_with('0') || !digits {
+            return None;
+        }
+
+        Some(Self { fill, align, width: width.parse().ok()? })
+    }
+
+    /// Split the padding needed for text of a given width.
+    fn split(&self, width: usize) -> (String, String) {
+        let pad = self.width.saturating_sub(width);
+        let (before, after) = match self.align {
+            Align::Left => (0, pad),
+            Align::Center => (pad / 2, pad - pad / 2),
+            Align::Right => (pad, 0),
+        };
+
+        let fill = |n: usize| std::iter::repeat_n(self.fill, n).collect();
+        (fill(before), fill(after))
+    }
+
+    /// Pad an argument, which must be a string, except in Literal Mode, where
+    ///     any literal may be padded. If its text can be found at compile-time,
+    ///     so can the padding. Otherwise, it must be measured at runtime, which
+    ///     requires the `sgr_runtime` crate, and is not possible in Const Mode.
+    fn apply(
+        &self,
+        expr: &syn::Expr,
+        behavior: &Behavior,
+    ) -> Option<syn::Expr> {
+        let is_literal = matches!(behavior.output, Output::Concat) || matches!(
+            expr,
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(..), .. }),
+        );
+        let is_colored = invocation(expr).is_some()
+            || hyperlink(expr).is_some()
+            || gradient(expr).is_some()
+            || matches!(expr, syn::Expr::Macro(m)
+                if macro_name(&m.mac).as_deref() == Some("sgr_markup"));
+
+        if !is_literal && !is_colored && concat(expr).is_none() {
+            return None;
+        }
+
+        if let Ok(text) = eval::literal(expr) {
+            let (before, after) = self.split(gate::strip(&text).width());
+            let before = syn::LitStr::new(&before, Span::call_site());
+            let after = syn::LitStr::new(&after, Span::call_site());
+
+            return Some(syn::parse_quote!(concat!(#before, #expr, #after)));
+        }
+
+        let is_const = matches!(behavior.output, Output::ConstFormat);
+        if !is_colored || is_const {
+            return None;
+        }
+
+        let value = if behavior.gated {
+            gate::gate_arg(expr.clone(), &behavior.output)
+        } else {
+            expr.clone()
+        };
+        let Self { fill, width, .. } = self;
+        let align = match self.align {
+            Align::Left => quote!(Left),
+            Align::Center => quote!(Center),
+            Align::Right => quote!(Right),
+        };
+
+        Some(syn::parse_quote!(::sgr_runtime::Pad {
+            value: #value,
+            width: #width,
+            align: ::sgr_runtime::Align::#align,
+            fill: #fill,
+        }))
+    }
+}
+
+
+/// Rewrite a template, so that any argument with a width is padded by the
+///     number of columns it occupies in a terminal, rather than by the number
+///     of characters in it, which includes control sequences.
+///
+/// Only arguments that are strings, calls to [`concat!`], or invocations of
+///     macros from this crate are padded this way, along with any literal in
+///     Literal Mode. The padding is moved from the placeholder into the
+///     argument.
+pub fn pad(
+    template: &syn::LitStr,
+    contents: &TokenStream,
+    behavior: &Behavior,
+) -> (syn::LitStr, TokenStream) {
+    let unchanged = || (template.clone(), contents.clone());
+    let text = template.value();
+
+    let (holes, mut args) = match (
+        placeholders(&text),
+        Args::parse_terminated.parse2(contents.clone()),
+    ) {
+        (Ok(holes), Ok(args)) => (holes, args),
+        _ => return unchanged(),
+    };
+
+    let positional: Vec<usize> = (0..args.len())
+        .filter(|&i| args[i].name.is_none())
+        .collect();
+    let find = |args: &Args, arg: &ArgRef| match arg {
+        ArgRef::Index(i) => positional.get(*i).copied(),
+        ArgRef::Name(name) => (0..args.len()).find(|&i| {
+            args[i].name.as_ref().is_some_and(|n| n == name)
+        }),
+    };
+
+    //  Find the padded argument of every placeholder first, since whether an
+    //      argument can be replaced depends on all the placeholders using it.
+    let pads: Vec<Option<(usize, syn::Expr)>> = holes.iter().map(|hole| {
+        let inner = &text[hole.start + 1..hole.end - 1];
+        let spec = inner.split_once(':').map_or("", |(_, spec)| spec);
+        let index = find(&args, &hole.arg)?;
+
+        Spec::parse(spec)?.apply(&args[index].expr, behavior)
+            .map(|expr| (index, expr))
+    }).collect();
+
+    let mut padded = String::with_capacity(text.len());
+    let mut extra = Vec::new();
+    let mut replaced: Vec<usize> = Vec::new();
+    let mut last = 0;
+
+    //  Once an implicit placeholder has been renamed, it no longer takes a
+    //      position, so every implicit placeholder after it must be given the
+    //      index it had.
+    let mut explicit = false;
+
+    for (hole, pad) in holes.iter().zip(pads.iter().cloned()) {
+        let inner = &text[hole.start + 1..hole.end - 1];
+        let (name, spec) = inner.split_once(':').unwrap_or((inner, ""));
+        let implicit = name.trim().is_empty();
+
+        let name = match hole.arg {
+            ArgRef::Index(i) if explicit && implicit => i.to_string(),
+            _ => String::from(name),
+        };
+
+        let Some((index, expr)) = pad else {
+            if explicit && implicit {
+                //  A precision of `.*` takes an implicit position of its own,
+                //      which cannot be given an index.
+                if spec.contains(".*") {
+                    return unchanged();
+                }
+
+                padded.push_str(&text[last..hole.start]);
+                last = hole.end;
+
+                match spec {
+                    "" => padded.push_str(&format!("{{{name}}}")),
+                    spec => padded.push_str(&format!("{{{name}:{spec}}}")),
+                }
+            }
+
+            continue;
+        };
+        //  The argument itself is replaced by the first padding of it, unless
+        //      another placeholder uses it without that padding.
+        let unpadded = holes.iter().zip(&pads).any(|(other, pad)| {
+            pad.is_none() && find(&args, &other.arg) == Some(index)
+        });
+        let shared = unpadded || replaced.contains(&index);
+
+        padded.push_str(&text[last..hole.start]);
+        last = hole.end;
+
+        if shared {
+            //  Other placeholders use the same argument without this padding,
+            //      so the padded argument must be a new one.
+            let name = format_ident!("__sgr_pad_{}", extra.len());
+            padded.push_str(&format!("{{{name}}}"));
+            extra.push(FormatArg { name: Some(name), expr });
+            explicit |= implicit;
+        } else {
+            padded.push_str(&format!("{{{name}}}"));
+            args[index].expr = expr;
+            replaced.push(index);
+        }
+    }
+
+    if last == 0 {
+        return unchanged();
+    }
+
+    padded.push_str(&text[last..]);
+    args.extend(extra);
+
+    (syn::LitStr::new(&padded, template.span()), args.into_token_stream())
+}
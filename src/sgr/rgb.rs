@@ -1,14 +1,72 @@
-use std::str::FromStr;
+mod mix;
+mod named;
+mod palette;
+mod space;
+
+pub use mix::Blend;
+
+use proc_macro2::Span;
+use std::{fmt::{self, Display, Formatter}, str::FromStr};
 use syn::{parenthesized, parse::{Parse, ParseStream}, Token};
 
 
-const fn accept_value(value: u32) -> Result<u32, &'static str> {
+pub const fn accept_value(value: u32) -> Result<u32, RgbError> {
     //  TODO: Maybe make this configurable somehow. Is there any situation where
     //      an alpha channel is meaningful for terminal text?
     if value <= 0x_00_FF_FF_FF {
         Ok(value)
     } else {
-        Err("RGB color value exceeds 24 bits")
+        Err(RgbError::AlphaSet((value >> 24) as u8))
+    }
+}
+
+
+/// A reason that a string could not be parsed as an RGB color.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RgbError {
+    /// The string is hexadecimal, but does not begin with `#` or `0x`.
+    MissingPrefix,
+    /// The string is not the name of a CSS color. The most similar name may be
+    ///     suggested.
+    UnknownName { name: String, suggestion: Option<&'static str> },
+    /// A character, at a given position in the string, is not a hexadecimal
+    ///     digit.
+    BadDigit { position: usize, found: char },
+    /// The number of hexadecimal digits is not 3 or 6 (or 8, with a zero alpha
+    ///     byte).
+    WrongLength(usize),
+    /// The value has a nonzero alpha byte.
+    AlphaSet(u8),
+    /// The string is a color function, such as `hsl(...)`, which could not be
+    ///     parsed.
+    Function(String),
+}
+
+impl Display for RgbError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => write!(
+                f, "RGB color string must begin with `#` or `0x`",
+            ),
+            Self::UnknownName { name, suggestion: Some(suggestion) } => write!(
+                f, "unknown color name `{name}`; did you mean `{suggestion}`?",
+            ),
+            Self::UnknownName { name, suggestion: None } => write!(
+                f, "unknown color name `{name}`",
+            ),
+            Self::BadDigit { position, found } => write!(
+                f, "invalid hexadecimal digit {found:?} at position {position}",
+            ),
+            Self::WrongLength(len) => write!(
+                f, "RGB color string must have 3 or 6 hexadecimal digits, \
+                not {len}",
+            ),
+            Self::AlphaSet(alpha) => write!(
+                f, "RGB color value exceeds 24 bits \
+                (alpha byte is {alpha:#04X})",
+            ),
+            Self::Function(msg) => f.write_str(msg),
+        }
     }
 }
 
@@ -34,33 +92,62 @@ pub struct Rgb {
     pub b: u8,
 }
 
+impl Rgb {
+    /// Find a color by its name in CSS.
+    pub fn from_name(name: &str) -> Result<Self, RgbError> {
+        match named::lookup(name) {
+            Some(value) => Ok(value.into()),
+            None => Err(RgbError::UnknownName {
+                name: String::from(name),
+                suggestion: named::suggest(name),
+            }),
+        }
+    }
+}
+
 impl FromStr for Rgb {
-    type Err = ();
+    type Err = RgbError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let inner_hex = s.strip_prefix("#")
+        if s.contains('(') {
+            return match syn::parse_str::<ColorFunction>(s) {
+                Ok(function) => Ok(function.0),
+                Err(err) => Err(RgbError::Function(err.to_string())),
+            };
+        }
+
+        let inner_hex = s.strip_prefix('#')
             .or_else(|| s.strip_prefix("0x"))
             .or_else(|| s.strip_prefix("0X"));
 
-        if let Some(number) = inner_hex {
-            if let Some(rrggbb) = expand_rgb_rrggbb(number) {
-                if let Ok(n) = u32::from_str_radix(&rrggbb, 16) {
-                    return Ok(n.into());
-                }
-            }
-
-            match u32::from_str_radix(number, 16) {
-                Ok(n) => Ok(n.into()),
-                Err(..) => Err(()),
-            }
-        } else {
-            // match s.parse::<u32>() {
-            //     Ok(n) => Ok(n.into()),
-            //     Err(..) => Err(()),
-            // }
+        let number = match inner_hex {
+            Some(number) => number,
+            None => return match Self::from_name(s) {
+                //  Hexadecimal digits without a prefix are most likely meant
+                //      as a hexadecimal value, rather than as a name.
+                Err(..) if !s.is_empty()
+                    && s.chars().all(|c| c.is_ascii_hexdigit())
+                    => Err(RgbError::MissingPrefix),
+                result => result,
+            },
+        };
+        let prefix = s.len() - number.len();
 
-            Err(())
+        if let Some((i, found)) = number.chars().enumerate()
+            .find(|(_, c)| !c.is_ascii_hexdigit())
+        {
+            return Err(RgbError::BadDigit { position: prefix + i, found });
         }
+
+        let value = match number.len() {
+            3 => u32::from_str_radix(&expand_rgb_rrggbb(number).unwrap(), 16),
+            6 | 8 => u32::from_str_radix(number, 16),
+            len => return Err(RgbError::WrongLength(len)),
+        };
+
+        //  All characters are hexadecimal digits, and there are at most eight
+        //      of them, so the value must fit.
+        Ok(accept_value(value.unwrap())?.into())
     }
 }
 
@@ -77,12 +164,21 @@ impl Parse for Rgb {
             let b = inner.parse::<RgbNumber>()?.0;
 
             Ok(Self { a: 0, r, g, b })
-        } else if let Ok(_) = input.fork().parse::<syn::LitStr>() {
+        } else if input.fork().parse::<syn::LitStr>().is_ok() {
             let literal = input.parse::<syn::LitStr>()?;
 
             match literal.value().parse::<Self>() {
                 Ok(rgb) => Ok(rgb),
-                Err(..) => todo!(),
+                Err(err) => Err(syn::Error::new(literal.span(), err)),
+            }
+        } else if input.peek(syn::Ident) && input.peek2(syn::token::Paren) {
+            Ok(input.parse::<ColorFunction>()?.0)
+        } else if input.peek(syn::Ident) {
+            let ident = input.parse::<syn::Ident>()?;
+
+            match Self::from_name(&ident.to_string()) {
+                Ok(rgb) => Ok(rgb),
+                Err(err) => Err(syn::Error::new(ident.span(), err)),
             }
         } else {
             let literal = input.parse::<syn::LitInt>()?;
@@ -93,7 +189,7 @@ impl Parse for Rgb {
 
             match accept_value(literal.base10_parse()?) {
                 Ok(color) => Ok(color.into()),
-                Err(text) => Err(syn::Error::new(literal.span(), text)),
+                Err(err) => Err(syn::Error::new(literal.span(), err)),
             }
         }
     }
@@ -121,7 +217,7 @@ impl Parse for RgbNumber {
         const MAX: f64 = u8::MAX as f64;
         const MIN: f64 = u8::MIN as f64;
 
-        if let Ok(_) = input.fork().parse::<syn::LitInt>() {
+        if input.fork().parse::<syn::LitInt>().is_ok() {
             let literal = input.parse::<syn::LitInt>()?;
             let numeral = literal.base10_parse::<u8>()?;
 
@@ -150,3 +246,141 @@ impl Parse for RgbNumber {
         }
     }
 }
+
+
+/// A single numeric argument to a color function, such as `50%` or `0.5turn`.
+struct Component {
+    value: f64,
+    unit: Unit,
+    span: Span,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Unit {
+    None,
+    Percent,
+    /// An angle, with the number of degrees in one of its units.
+    Angle(f64),
+}
+
+impl Component {
+    /// Interpret this component as a hue, in degrees.
+    fn hue(&self) -> syn::Result<f64> {
+        match self.unit {
+            Unit::None => Ok(self.value),
+            Unit::Angle(degrees) => Ok(self.value * degrees),
+            Unit::Percent => Err(self.error("hue cannot be a percentage")),
+        }
+    }
+
+    /// Interpret this component as a fraction. A plain number is divided by
+    ///     `max`, while a percentage is scaled to `percent`.
+    fn fraction(&self, max: f64, percent: f64) -> syn::Result<f64> {
+        match self.unit {
+            Unit::None => Ok(self.value / max),
+            Unit::Percent => Ok(self.value / 100.0 * percent),
+            Unit::Angle(..) => Err(self.error("only a hue may be an angle")),
+        }
+    }
+
+    fn error(&self, msg: &str) -> syn::Error {
+        syn::Error::new(self.span, msg)
+    }
+}
+
+impl Parse for Component {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let sign = if input.parse::<Token![-]>().is_ok() { -1.0 } else { 1.0 };
+
+        let (value, suffix, span) = if input.peek(syn::LitFloat) {
+            let literal = input.parse::<syn::LitFloat>()?;
+            let suffix = String::from(literal.suffix());
+            (literal.base10_parse::<f64>()?, suffix, literal.span())
+        } else {
+            let literal = input.parse::<syn::LitInt>()?;
+            let suffix = String::from(literal.suffix());
+            (literal.base10_parse::<f64>()?, suffix, literal.span())
+        };
+
+        let unit = match suffix.as_str() {
+            "" if input.parse::<Token![%]>().is_ok() => Unit::Percent,
+            "" | "deg" => Unit::None,
+            "grad" => Unit::Angle(0.9),
+            "rad" => Unit::Angle(180.0 / std::f64::consts::PI),
+            "turn" => Unit::Angle(360.0),
+            other => return Err(syn::Error::new(
+                span,
+                format!("unknown unit `{other}`; expected `%`, `deg`, `grad`, \
+                `rad` or `turn`"),
+            )),
+        };
+
+        Ok(Self { value: sign * value, unit, span })
+    }
+}
+
+
+/// A color written in the functional notation of CSS, such as `rgb(255 127 0)`
+///     or `hsl(210, 40%, 50%)`, converted to 24-bit sRGB.
+struct ColorFunction(Rgb);
+
+impl Parse for ColorFunction {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse::<syn::Ident>()?;
+        let inner;
+        parenthesized!(inner in input);
+
+        //  Components may be separated by either commas or whitespace.
+        let mut parts = Vec::with_capacity(3);
+
+        while !inner.is_empty() {
+            if inner.peek(Token![/]) {
+                return Err(inner.error("alpha channels are not supported"));
+            }
+
+            parts.push(inner.parse::<Component>()?);
+            let _ = inner.parse::<Token![,]>();
+        }
+
+        let [a, b, c] = match <[Component; 3]>::try_from(parts) {
+            Ok(parts) => parts,
+            Err(parts) => return Err(syn::Error::new(
+                name.span(),
+                format!("`{name}()` takes 3 components, not {}", parts.len()),
+            )),
+        };
+
+        let unit = |x: f64| x.clamp(0.0, 1.0);
+
+        let channels = match name.to_string().as_str() {
+            "rgb" => [
+                a.fraction(255.0, 1.0)?,
+                b.fraction(255.0, 1.0)?,
+                c.fraction(255.0, 1.0)?,
+            ],
+            "hsl" => space::hsl_to_srgb(
+                a.hue()?,
+                unit(b.fraction(100.0, 1.0)?),
+                unit(c.fraction(100.0, 1.0)?),
+            ),
+            "hsv" | "hsb" => space::hsv_to_srgb(
+                a.hue()?,
+                unit(b.fraction(100.0, 1.0)?),
+                unit(c.fraction(100.0, 1.0)?),
+            ),
+            "oklch" => space::oklch_to_srgb(
+                unit(a.fraction(1.0, 1.0)?),
+                b.fraction(1.0, 0.4)?.max(0.0),
+                c.hue()?,
+            ),
+            _ => return Err(syn::Error::new(
+                name.span(),
+                format!("unknown color function `{name}()`; expected \
+                `rgb()`, `hsl()`, `hsv()` or `oklch()`"),
+            )),
+        };
+
+        let [r, g, b] = channels.map(space::to_u8);
+        Ok(Self(Rgb { a: 0, r, g, b }))
+    }
+}
@@ -0,0 +1,85 @@
+//  Interpolation between colors, for gradients.
+
+use super::{space, Rgb};
+
+
+/// The color space in which colors are mixed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Blend {
+    /// Linear RGB, in which colors mix as light does.
+    Linear,
+    /// OKLab, in which the steps between colors are perceptually even.
+    #[default]
+    Oklab,
+}
+
+
+impl Rgb {
+    fn srgb(&self) -> [f64; 3] {
+        let Rgb { a: _, r, g, b } = *self;
+        [r, g, b].map(|c| f64::from(c) / 255.0)
+    }
+
+    /// Find the color at some fraction of the way from this color to another.
+    pub fn mix(&self, other: &Self, t: f64, blend: Blend) -> Self {
+        let lerp = |a: [f64; 3], b: [f64; 3]| {
+            [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t)
+        };
+
+        let srgb = match blend {
+            Blend::Linear => lerp(
+                self.srgb().map(space::srgb_to_linear),
+                other.srgb().map(space::srgb_to_linear),
+            ).map(space::linear_to_srgb),
+            Blend::Oklab => space::oklab_to_srgb(lerp(
+                space::srgb_to_oklab(self.srgb()),
+                space::srgb_to_oklab(other.srgb()),
+            )),
+        };
+
+        let [r, g, b] = srgb.map(space::to_u8);
+        Self { a: 0, r, g, b }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_ends() {
+        let colors = [0x000000, 0xFFFFFF, 0xFF0000, 0x4682B4, 0x123456];
+
+        for a in colors.map(Rgb::from) {
+            for b in colors.map(Rgb::from) {
+                for blend in [Blend::Linear, Blend::Oklab] {
+                    assert_eq!(a.mix(&b, 0.0, blend), a);
+                    assert_eq!(a.mix(&b, 1.0, blend), b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mix_linear() {
+        let red = Rgb::from(0xFF0000);
+        let blue = Rgb::from(0x0000FF);
+
+        //  Half of full intensity is brighter than half of 255 in sRGB.
+        assert_eq!(red.mix(&blue, 0.5, Blend::Linear), Rgb::from(0xBC00BC));
+        assert_eq!(
+            Rgb::from(0x000000).mix(&Rgb::from(0xFFFFFF), 0.5, Blend::Linear),
+            Rgb::from(0xBCBCBC),
+        );
+    }
+
+    #[test]
+    fn test_mix_oklab() {
+        //  Gray is perceptually halfway between black and white.
+        let (black, white) = (Rgb::from(0x000000), Rgb::from(0xFFFFFF));
+        let gray = black.mix(&white, 0.5, Blend::Oklab);
+        assert_eq!((gray.r, gray.g), (gray.g, gray.b));
+        assert!((0x5E..=0x66).contains(&gray.r), "{gray:?}");
+    }
+}
@@ -0,0 +1,92 @@
+//  Approximation of 24-bit colors by the indexed palettes of terminals without
+//      support for them.
+
+use super::{space, Rgb};
+
+
+/// Get the 24-bit value of a color in the xterm 256-color palette.
+pub fn xterm_256(index: u8) -> Rgb {
+    let (r, g, b) = sgr_runtime::xterm_rgb(index);
+    Rgb { a: 0, r, g, b }
+}
+
+
+/// Find the palette index, among some candidates, whose color is perceptually
+///     nearest to a given color. Ties go to the earliest candidate.
+fn nearest(rgb: &Rgb, candidates: impl Iterator<Item = u8>) -> u8 {
+    let target = rgb.oklab();
+    let distance = |index: &u8| {
+        let other = xterm_256(*index).oklab();
+        (0..3).map(|i| (target[i] - other[i]).powi(2)).sum::<f64>()
+    };
+
+    candidates.min_by(|a, b| distance(a).total_cmp(&distance(b))).unwrap()
+}
+
+
+impl Rgb {
+    fn oklab(&self) -> [f64; 3] {
+        let Rgb { a: _, r, g, b } = *self;
+        space::srgb_to_oklab([r, g, b].map(|c| f64::from(c) / 255.0))
+    }
+
+    /// Find the nearest color in the xterm 256-color palette. The 16 basic
+    ///     colors are not considered, since their values vary by terminal.
+    pub fn nearest_256(&self) -> u8 {
+        nearest(self, 16..=255)
+    }
+
+    /// Find the nearest of the 16 basic colors, as an index from 0 to 15.
+    pub fn nearest_16(&self) -> u8 {
+        nearest(self, 0..16)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xterm_256() {
+        assert_eq!(xterm_256(1), Rgb::from(0xCD0000));
+        assert_eq!(xterm_256(16), Rgb::from(0x000000));
+        assert_eq!(xterm_256(67), Rgb::from(0x5F87AF));
+        assert_eq!(xterm_256(208), Rgb::from(0xFF8700));
+        assert_eq!(xterm_256(231), Rgb::from(0xFFFFFF));
+        assert_eq!(xterm_256(232), Rgb::from(0x080808));
+        assert_eq!(xterm_256(255), Rgb::from(0xEEEEEE));
+    }
+
+    #[test]
+    fn test_nearest_exact() {
+        //  Every color in the palette should be its own nearest match.
+        for index in 16..=255 {
+            assert_eq!(xterm_256(index).nearest_256(), index);
+        }
+
+        for index in 0..16 {
+            assert_eq!(xterm_256(index).nearest_16(), index);
+        }
+    }
+
+    #[test]
+    fn test_nearest_256() {
+        assert_eq!(Rgb::from(0xFF0000).nearest_256(), 196);
+        assert_eq!(Rgb::from(0xFF8800).nearest_256(), 208);
+        assert_eq!(Rgb::from(0x5F87AA).nearest_256(), 67);
+        assert_eq!(Rgb::from(0x808080).nearest_256(), 244);
+        assert_eq!(Rgb::from(0x111111).nearest_256(), 233);
+        assert_eq!(Rgb::from(0xFDFDFD).nearest_256(), 231);
+    }
+
+    #[test]
+    fn test_nearest_16() {
+        assert_eq!(Rgb::from(0x000000).nearest_16(), 0);
+        assert_eq!(Rgb::from(0xB22222).nearest_16(), 1);
+        assert_eq!(Rgb::from(0xFF4500).nearest_16(), 9);
+        assert_eq!(Rgb::from(0x1010E0).nearest_16(), 4);
+        assert_eq!(Rgb::from(0xC0C0C0).nearest_16(), 7);
+        assert_eq!(Rgb::from(0x696969).nearest_16(), 8);
+    }
+}
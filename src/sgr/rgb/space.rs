@@ -0,0 +1,146 @@
+//  All channels here are in the range of 0.0 to 1.0, unless noted otherwise.
+//      Hues are in degrees.
+
+
+/// Convert an HSL color to sRGB.
+pub fn hsl_to_srgb(hue: f64, sat: f64, light: f64) -> [f64; 3] {
+    let chroma = (1.0 - (2.0 * light - 1.0).abs()) * sat;
+    hue_to_srgb(hue, chroma, light - chroma / 2.0)
+}
+
+
+/// Convert an HSV color to sRGB.
+pub fn hsv_to_srgb(hue: f64, sat: f64, value: f64) -> [f64; 3] {
+    let chroma = value * sat;
+    hue_to_srgb(hue, chroma, value - chroma)
+}
+
+
+fn hue_to_srgb(hue: f64, chroma: f64, min: f64) -> [f64; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let [r, g, b] = match sector as u8 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+
+    [r + min, g + min, b + min]
+}
+
+
+/// Convert an OKLCH color to sRGB. Chroma is unbounded, but rarely exceeds
+///     0.4 within sRGB.
+///
+/// Colors outside the sRGB gamut are mapped into it by reducing chroma, which
+///     preserves lightness and hue.
+pub fn oklch_to_srgb(light: f64, chroma: f64, hue: f64) -> [f64; 3] {
+    const EPSILON: f64 = 1e-6;
+
+    let linear_at = |chroma: f64| {
+        let (sin, cos) = hue.to_radians().sin_cos();
+        oklab_to_linear(light, chroma * cos, chroma * sin)
+    };
+    let in_gamut = |rgb: &[f64; 3]| rgb.iter()
+        .all(|c| (-EPSILON..=1.0 + EPSILON).contains(c));
+
+    let linear = if light >= 1.0 {
+        [1.0; 3]
+    } else if light <= 0.0 {
+        [0.0; 3]
+    } else if in_gamut(&linear_at(chroma)) {
+        linear_at(chroma)
+    } else {
+        let (mut lo, mut hi) = (0.0, chroma);
+
+        while hi - lo > EPSILON {
+            let mid = (lo + hi) / 2.0;
+
+            if in_gamut(&linear_at(mid)) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        linear_at(lo)
+    };
+
+    linear.map(linear_to_srgb)
+}
+
+
+fn oklab_to_linear(light: f64, a: f64, b: f64) -> [f64; 3] {
+    let l = light + 0.396_337_777_4 * a + 0.215_803_757_3 * b;
+    let m = light - 0.105_561_345_8 * a - 0.063_854_172_8 * b;
+    let s = light - 0.089_484_177_5 * a - 1.291_485_548_0 * b;
+
+    let (l, m, s) = (l.powi(3), m.powi(3), s.powi(3));
+
+    [
+        4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+        -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+        -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s,
+    ]
+}
+
+
+/// Convert a channel of linear RGB to sRGB, clamping it into range.
+pub fn linear_to_srgb(c: f64) -> f64 {
+    let c = c.clamp(0.0, 1.0);
+
+    if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+
+/// Convert an OKLab color to sRGB. Unlike [`oklch_to_srgb`], colors outside
+///     the sRGB gamut are clamped into it.
+pub fn oklab_to_srgb(lab: [f64; 3]) -> [f64; 3] {
+    let [light, a, b] = lab;
+    oklab_to_linear(light, a, b).map(linear_to_srgb)
+}
+
+
+/// Convert an sRGB color to OKLab, in which Euclidean distance approximates
+///     perceived difference.
+pub fn srgb_to_oklab(rgb: [f64; 3]) -> [f64; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+
+    let l = 0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b;
+    let m = 0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b;
+    let s = 0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b;
+
+    let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+    [
+        0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s,
+        1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s,
+        0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s,
+    ]
+}
+
+
+/// Convert a channel of sRGB to linear RGB, in which light mixes evenly.
+pub fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+
+/// Convert a channel to an 8-bit value, clamping it into range.
+pub fn to_u8(c: f64) -> u8 {
+    //  Nudge exact halves upward, where floating-point error would otherwise
+    //      round them down.
+    (c.clamp(0.0, 1.0) * 255.0 + 1e-9).round() as u8
+}
@@ -0,0 +1,151 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use super::nested::Args;
+
+
+/// The argument referred to by a placeholder in a template.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgRef {
+    Index(usize),
+    Name(String),
+}
+
+
+/// A single `{...}` placeholder in a template.
+#[derive(Clone, Debug)]
+pub struct Placeholder {
+    pub arg: ArgRef,
+    /// Byte offset of the opening brace.
+    pub start: usize,
+    /// Byte offset immediately after the closing brace.
+    pub end: usize,
+}
+
+
+/// Find every placeholder in a template, resolving implicit positional
+///     arguments to their indices.
+pub fn placeholders(template: &str) -> Result<Vec<Placeholder>, String> {
+    let bytes = template.as_bytes();
+    let mut found = Vec::new();
+    let mut next_pos = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' if bytes.get(i + 1) == Some(&b'{') => i += 2,
+            b'}' if bytes.get(i + 1) == Some(&b'}') => i += 2,
+            b'{' => {
+                let start = i;
+                let len = template[start..].find('}')
+                    .ok_or("unmatched `{` in format string")?;
+                let end = start + len + 1;
+                let inner = &template[start + 1..end - 1];
+
+                if inner.contains('{') {
+                    return Err(String::from("unmatched `{` in format string"));
+                }
+
+                let (arg, spec) = match inner.split_once(':') {
+                    Some((arg, spec)) => (arg.trim(), spec),
+                    None => (inner.trim(), ""),
+                };
+
+                //  A precision of `.*` takes the next positional argument,
+                //      before the value itself.
+                if spec.contains(".*") {
+                    next_pos += 1;
+                }
+
+                let arg = if arg.is_empty() {
+                    next_pos += 1;
+                    ArgRef::Index(next_pos - 1)
+                } else if let Ok(index) = arg.parse() {
+                    ArgRef::Index(index)
+                } else {
+                    ArgRef::Name(String::from(arg))
+                };
+
+                found.push(Placeholder { arg, start, end });
+
+                i = end;
+            }
+            b'}' => return Err(String::from("unmatched `}` in format string")),
+            _ => i += 1,
+        }
+    }
+
+    Ok(found)
+}
+
+
+/// Fill the placeholders of a Literal Mode template with its arguments. The
+///     result is a list of items for [`concat!`], in which each argument is
+///     kept as it was given, so that nested invocations can still be found.
+///
+/// A placeholder may not have a format spec, as padding must already have
+///     been moved from the template into the arguments.
+pub fn interpolate(
+    template: &syn::LitStr,
+    args: &Args,
+) -> syn::Result<TokenStream> {
+    let text = template.value();
+    let holes = placeholders(&text)
+        .map_err(|msg| syn::Error::new(template.span(), msg))?;
+
+    let positional: Vec<usize> = (0..args.len())
+        .filter(|&i| args[i].name.is_none())
+        .collect();
+    let mut used = vec![false; args.len()];
+    let mut items: Vec<TokenStream> = Vec::new();
+    let mut last = 0;
+
+    let unescape = |s: &str| s.replace("{{", "{").replace("}}", "}");
+
+    for hole in &holes {
+        let index = match &hole.arg {
+            ArgRef::Index(i) => positional.get(*i).copied(),
+            ArgRef::Name(name) => (0..args.len()).find(|&i| {
+                args[i].name.as_ref().is_some_and(|n| n == name)
+            }),
+        };
+
+        let Some(index) = index else {
+            let msg = match &hole.arg {
+                ArgRef::Index(i) => format!("there is no positional argument \
+                {i}"),
+                ArgRef::Name(name) => format!("there is no argument named \
+                `{name}`; Literal Mode cannot capture variables"),
+            };
+
+            return Err(syn::Error::new(template.span(), msg));
+        };
+
+        if text[hole.start + 1..hole.end - 1].contains(':') {
+            return Err(syn::Error::new(
+                template.span(),
+                "Literal Mode only supports a fill, alignment, and width in \
+                a placeholder, for an argument whose text is known",
+            ));
+        }
+
+        let before = unescape(&text[last..hole.start]);
+        let expr = &args[index].expr;
+
+        items.push(quote!(#before));
+        items.push(quote!(#expr));
+        used[index] = true;
+        last = hole.end;
+    }
+
+    if let Some(unused) = used.iter().position(|used| !used) {
+        return Err(syn::Error::new_spanned(
+            &args[unused].expr,
+            "argument never used",
+        ));
+    }
+
+    let after = unescape(&text[last..]);
+    items.push(quote!(#after));
+
+    Ok(quote!(#(#items),*))
+}
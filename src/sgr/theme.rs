@@ -0,0 +1,364 @@
+use std::{ops::Range, path::PathBuf};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse::{Parse, ParseStream}, Token};
+use toml_edit::{ImDocument, Item};
+use super::{alias::define, find_style, rgb::{accept_value, Rgb}};
+
+
+/// The keys accepted in the table of a role.
+const KEYS: &str = "`style`, `fg`, `bg`, `ul`, `color_256`, `color_256_bg` \
+or `uline_color_256`";
+
+
+/// A problem with an entry of a theme file, and where it was found.
+#[derive(Debug)]
+struct Problem {
+    span: Option<Range<usize>>,
+    key: String,
+    msg: String,
+}
+
+impl Problem {
+    fn new(item: &Item, key: &str, msg: impl Into<String>) -> Self {
+        Self { span: item.span(), key: key.into(), msg: msg.into() }
+    }
+
+    /// Describe this problem, with the line and column of the entry in the
+    ///     theme file.
+    fn describe(&self, file: &str, text: &str) -> String {
+        let Self { span, key, msg } = self;
+
+        let place = match span {
+            Some(span) => {
+                let before = &text[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let start = before.rfind('\n').map_or(0, |i| i + 1);
+                let col = before[start..].chars().count() + 1;
+
+                format!("{file}:{line}:{col}")
+            }
+            None => String::from(file),
+        };
+
+        if key.is_empty() {
+            format!("{place}: {msg}")
+        } else {
+            format!("{place}: `{key}`: {msg}")
+        }
+    }
+}
+
+
+/// A semantic role defined by a theme, such as `error` or `path`.
+struct Role {
+    name: String,
+    /// The attributes of the role, as they would be given to [`sgr!`].
+    ///
+    /// [`sgr!`]: crate::sgr!
+    attrs: Vec<TokenStream>,
+}
+
+impl Role {
+    fn parse(name: &str, item: &Item, problems: &mut Vec<Problem>) -> Self {
+        let mut role = Self { name: String::from(name), attrs: Vec::new() };
+
+        if let Some(table) = item.as_table_like() {
+            for (key, value) in table.iter() {
+                let path = format!("{name}.{key}");
+
+                //  Use the span of the key, if the value has none.
+                let value_span = table.get_key_value(key)
+                    .and_then(|(k, _)| k.span());
+                let located = |msg: String| Problem {
+                    span: value.span().or(value_span.clone()),
+                    key: path.clone(),
+                    msg,
+                };
+
+                let result = match key {
+                    "style" => role.parse_styles(value, &path, problems),
+                    "fg" | "bg" | "ul" => parse_rgb(value).map(|rgb| {
+                        let key = format_ident!("{}", key);
+                        let value = syn::LitInt::new(
+                            &format!("{:#08X}", u32::from(rgb)),
+                            Span::call_site(),
+                        );
+
+                        role.attrs.push(quote!(#key = #value));
+                    }),
+                    "color_256" | "color_256_bg" | "uline_color_256" => {
+                        match value.as_integer().map(u8::try_from) {
+                            Some(Ok(index)) => {
+                                let key = format_ident!("{}", key);
+                                role.attrs.push(quote!(#key = #index));
+                                Ok(())
+                            }
+                            _ => Err(String::from(
+                                "indexed color must be an integer from 0 to \
+                                255",
+                            )),
+                        }
+                    }
+                    _ => Err(format!("unknown key; expected {KEYS}")),
+                };
+
+                if let Err(msg) = result {
+                    problems.push(located(msg));
+                }
+            }
+        } else if let Err(msg) = role.parse_styles(item, name, problems) {
+            problems.push(Problem::new(item, name, msg));
+        }
+
+        if role.attrs.is_empty() && problems.is_empty() {
+            problems.push(Problem::new(item, name, "role has no attributes"));
+        }
+
+        role
+    }
+
+    /// Add the styles named by a string, or by an array of strings.
+    fn parse_styles(
+        &mut self,
+        item: &Item,
+        key: &str,
+        problems: &mut Vec<Problem>,
+    ) -> Result<(), String> {
+        let names: Vec<(&str, Option<Range<usize>>)> = if let Some(name)
+            = item.as_str()
+        {
+            vec![(name, item.span())]
+        } else if let Some(array) = item.as_array() {
+            array.iter()
+                .map(|value| Ok((value.as_str().ok_or(())?, value.span())))
+                .collect::<Result<_, ()>>()
+                .map_err(|()| "styles must be strings")?
+        } else {
+            return Err(String::from(
+                "expected a style name, an array of style names, or a table",
+            ));
+        };
+
+        for (name, span) in names {
+            if find_style(name).is_some() {
+                self.attrs.push(format_ident!("{}", name).into_token_stream());
+            } else {
+                problems.push(Problem {
+                    span: span.or_else(|| item.span()),
+                    key: String::from(key),
+                    msg: format!(
+                        "unknown style `{name}`; expected the name of a basic \
+                        color or style macro, such as `red` or `bold`",
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+/// Read an RGB color from a string, in any format accepted by [`color_rgb!`],
+///     or from an integer.
+///
+/// [`color_rgb!`]: crate::color_rgb!
+fn parse_rgb(item: &Item) -> Result<Rgb, String> {
+    if let Some(s) = item.as_str() {
+        s.parse().map_err(|err| format!("{err}"))
+    } else if let Some(value) = item.as_integer() {
+        match u32::try_from(value) {
+            Ok(value) => match accept_value(value) {
+                Ok(value) => Ok(value.into()),
+                Err(err) => Err(format!("{err}")),
+            },
+            Err(..) => Err(String::from("RGB color value cannot be negative")),
+        }
+    } else {
+        Err(String::from("expected an RGB color string or integer"))
+    }
+}
+
+
+/// Read every role from the text of a theme file.
+fn load(text: &str) -> Result<Vec<Role>, Vec<Problem>> {
+    let doc = ImDocument::parse(text).map_err(|err| vec![Problem {
+        span: err.span(),
+        key: String::new(),
+        msg: match err.message().trim() {
+            "" => String::from("invalid TOML"),
+            msg => format!("invalid TOML: {msg}"),
+        },
+    }])?;
+
+    let mut problems = Vec::new();
+    let mut roles = Vec::new();
+
+    for (name, item) in doc.as_table().iter() {
+        if syn::parse_str::<syn::Ident>(&format!("theme_{name}")).is_err() {
+            problems.push(Problem::new(
+                item,
+                name,
+                "role name must be a valid identifier",
+            ));
+            continue;
+        }
+
+        let mut found = Vec::new();
+        let role = Role::parse(name, item, &mut found);
+
+        if found.is_empty() {
+            roles.push(role);
+        } else {
+            problems.extend(found);
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(roles)
+    } else {
+        Err(problems)
+    }
+}
+
+
+/// A set of semantic roles, read from a TOML file at compile-time, each of
+///     which becomes a macro.
+pub struct SgrTheme {
+    path: PathBuf,
+    roles: Vec<Role>,
+}
+
+impl Parse for SgrTheme {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let file: syn::LitStr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+
+        let fail = |msg: String| syn::Error::new(file.span(), msg);
+
+        let root = std::env::var_os("CARGO_MANIFEST_DIR").ok_or_else(|| {
+            fail(String::from("CARGO_MANIFEST_DIR is not set"))
+        })?;
+        let path = PathBuf::from(root).join(file.value());
+        let text = std::fs::read_to_string(&path).map_err(|err| fail(format!(
+            "failed to read theme file {}: {err}",
+            path.display(),
+        )))?;
+
+        match load(&text) {
+            Ok(roles) => Ok(Self { path, roles }),
+            Err(problems) => {
+                let name = file.value();
+                let mut errors = problems.iter()
+                    .map(|problem| fail(problem.describe(&name, &text)));
+                let mut error = errors.next().unwrap();
+
+                error.extend(errors);
+                Err(error)
+            }
+        }
+    }
+}
+
+impl ToTokens for SgrTheme {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        //  Including the file, even though its contents are unused, causes the
+        //      crate to be rebuilt when it changes.
+        let path = self.path.to_string_lossy();
+        tokens.extend(quote!(const _: &[u8] = include_bytes!(#path);));
+
+        for Role { name, attrs } in &self.roles {
+            let name = format_ident!("theme_{}", name);
+            tokens.extend(define(&[], &name, attrs));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(text: &str) -> Vec<String> {
+        match load(text) {
+            Ok(..) => Vec::new(),
+            Err(problems) => problems.iter()
+                .map(|problem| problem.describe("theme.toml", text))
+                .collect(),
+        }
+    }
+
+    fn attrs(text: &str) -> Vec<(String, String)> {
+        load(text).unwrap().into_iter()
+            .map(|Role { name, attrs }| {
+                let attrs = quote!(#(#attrs),*).to_string();
+                (name, attrs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_load() {
+        assert_eq!(
+            attrs(concat!(
+                "error = [\"bold\", \"red\"]\n",
+                "path = \"sgr_uline\"\n",
+                "\n",
+                "[warning]\n",
+                "style = \"italic\"\n",
+                "fg = \"#FFAA00\"\n",
+                "bg = 0x112233\n",
+                "ul = \"hsl(0, 100%, 50%)\"\n",
+                "uline_color_256 = 9\n",
+            )),
+            [
+                (String::from("error"), String::from("bold , red")),
+                (String::from("path"), String::from("sgr_uline")),
+                (String::from("warning"), String::from(
+                    "italic , fg = 0xFFAA00 , bg = 0x112233 , \
+                    ul = 0xFF0000 , uline_color_256 = 9u8",
+                )),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_problems() {
+        assert_eq!(
+            problems("error = [\"bold\", \"blod\"]"),
+            ["theme.toml:1:18: `error`: unknown style `blod`; expected the \
+            name of a basic color or style macro, such as `red` or `bold`"],
+        );
+        assert_eq!(
+            problems("[path]\nstyle = \"uline\"\nfg = \"#GG0000\""),
+            ["theme.toml:3:6: `path.fg`: invalid hexadecimal digit 'G' at \
+            position 1"],
+        );
+        assert_eq!(
+            problems("[path]\nfg = \"redd\"\nsize = 2"),
+            [
+                "theme.toml:2:6: `path.fg`: unknown color name `redd`; did \
+                you mean `red`?",
+                "theme.toml:3:8: `path.size`: unknown key; expected `style`, \
+                `fg`, `bg`, `ul`, `color_256`, `color_256_bg` or \
+                `uline_color_256`",
+            ],
+        );
+        assert_eq!(
+            problems("a = { color_256 = 256 }\nb = []\n\"c-d\" = \"bold\""),
+            [
+                "theme.toml:1:19: `a.color_256`: indexed color must be an \
+                integer from 0 to 255",
+                "theme.toml:2:5: `b`: role has no attributes",
+                "theme.toml:3:9: `c-d`: role name must be a valid identifier",
+            ],
+        );
+        assert_eq!(problems("error = "), ["theme.toml:1:9: invalid TOML"]);
+        assert_eq!(
+            problems("error = \"red\"\nerror = \"bold\""),
+            ["theme.toml:2:1: invalid TOML: duplicate key `error` in document \
+            root"],
+        );
+    }
+}
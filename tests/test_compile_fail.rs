@@ -0,0 +1,8 @@
+#[test]
+fn test_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+
+    #[cfg(not(feature = "const"))]
+    t.compile_fail("tests/ui-no-const/*.rs");
+}
@@ -0,0 +1,90 @@
+//! Gated output, in a test binary of its own, since the override of color
+//!     support is shared by every test in a process.
+
+use sgr_macros::*;
+
+
+#[test]
+fn test_sgr_gated() {
+    use std::fmt::Write;
+    use sgr_runtime::{set_override, Stream};
+
+    //  Literal Mode produces both outputs, and so does not depend on runtime.
+    const PAIR: (&str, &str) = red!(? concat!("a ", green!("b"), " c"));
+    assert_eq!(
+        PAIR,
+        ("\x1B[31ma \x1B[32mb\x1B[31m c\x1B[39m", "a b c"),
+    );
+    assert_eq!(
+        sgr_markup!(? "<b>bold</b> text"),
+        ("\x1B[1mbold\x1B[22m text", "bold text"),
+    );
+
+    set_override(Some(true));
+    assert!(sgr_runtime::enabled(Stream::Stdout));
+    assert_eq!(sgr_runtime::select(PAIR, Stream::Stdout), PAIR.0);
+    assert_eq!(
+        red!(@? "a {} {x} c", green!("b"), x = blue!(@? "{}", 1)),
+        concat!(
+            "\x1B[31ma \x1B[32mb\x1B[39m\x1B[31m ",
+            "\x1B[34m1\x1B[39m\x1B[31m c\x1B[39m",
+        ),
+    );
+    assert_eq!(
+        format!("{}", sgr_bold!(%?* "{{{}}}", 1)),
+        "\x1B[1m{1}\x1B[m",
+    );
+    assert_eq!(
+        sgr_markup!(@? "<b>{}</b>", 1),
+        "\x1B[1m1\x1B[22m",
+    );
+
+    assert_eq!(
+        sgr_bold!(@? "{:^5}|{:>3}", red!("日"), green!(@? "{}", 1)),
+        concat!(
+            "\x1B[1m \x1B[31m日\x1B[39m  |",
+            "  \x1B[32m1\x1B[39m\x1B[22m",
+        ),
+    );
+
+    let url = "https://a.b";
+    assert_eq!(
+        hyperlink!(url, id = 1; @? "{}", red!("link")),
+        "\x1B]8;id=1;https://a.b\x1B\\\x1B[31mlink\x1B[39m\x1B]8;;\x1B\\",
+    );
+
+    set_override(Some(false));
+    assert!(!sgr_runtime::enabled(Stream::Stdout));
+    assert_eq!(sgr_runtime::select(PAIR, Stream::Stdout), PAIR.1);
+    assert_eq!(
+        red!(@? "a {} {x} c", green!("b"), x = blue!(@? "{}", 1)),
+        "a b 1 c",
+    );
+    assert_eq!(format!("{}", sgr_bold!(%?* "{{{}}}", 1)), "{1}");
+    assert_eq!(sgr_markup!(@? "<b>{}</b>", 1), "1");
+
+    let mut text = String::new();
+    sgr_bold!(> text; ? "{}", red!("x")).unwrap();
+    assert_eq!(text, "x");
+
+    //  A lazy value is only gated once it is displayed.
+    let lazy = red!(& move? "{}", 1);
+    assert_eq!(lazy.to_string(), "1");
+    set_override(Some(true));
+    assert_eq!(lazy.to_string(), "\x1B[31m1\x1B[39m");
+    set_override(Some(false));
+    assert_eq!(
+        sgr_bold!(@? "{:^5}|{:>3}", red!("日"), green!(@? "{}", 1)),
+        " 日  |  1",
+    );
+
+    assert_eq!(hyperlink!(url; @? "{}", red!("link")), "link");
+    assert_eq!(hyperlink!("https://a.b"; ? red!("link")).1, "link");
+
+    //  Arguments are evaluated only once.
+    let mut count = 0;
+    let mut next = || { count += 1; count };
+    assert_eq!(sgr!(bold, red; @? "{} {}", next(), next()), "1 2");
+
+    set_override(None);
+}
@@ -35,6 +35,45 @@ fn test_sgr_concat() {
 }
 
 
+#[test]
+fn test_sgr_concat_template() {
+    const ITEMS: &str = green!("{} items", "3");
+    assert_eq!(ITEMS, "\x1B[32m3 items\x1B[39m");
+    assert_eq!(
+        concat!(green!("{} items", "3"), "!"),
+        "\x1B[32m3 items\x1B[39m!",
+    );
+
+    assert_eq!(
+        green!("{}{{{n}}} {1} {0}", 1, n = 2.5, 'c'),
+        "\x1B[32m1{2.5} c 1\x1B[39m",
+    );
+    assert_eq!(
+        green!(! "{x}, {}", blue!("y"), x = concat!("x", 1)),
+        "\x1B[32mx1, \x1B[34my\x1B[32m",
+    );
+
+    //  A nested invocation is restored after, and padded by its visible width.
+    assert_eq!(
+        sgr_bold!("[{:>3}] {}", red!("x"), sgr_faint!("y")),
+        "\x1B[1m[  \x1B[31mx\x1B[39m] \x1B[2my\x1B[22m",
+    );
+    assert_eq!(
+        sgr_bold!(? "{:-^5}", red!("x")),
+        ("\x1B[1m--\x1B[31mx\x1B[39m--\x1B[22m", "--x--"),
+    );
+    assert_eq!(sgr_strip!(green!("{} items", blue!("3"))), "3 items");
+    assert_eq!(
+        hyperlink!("https://a.b"; "{} link", "a"),
+        "\x1B]8;;https://a.b\x1B\\a link\x1B]8;;\x1B\\",
+    );
+
+    //  Without placeholders or arguments, a literal is not a template.
+    assert_eq!(green!("{}"), "\x1B[32m{}\x1B[39m");
+    assert_eq!(green!("{{}}", "x"), "\x1B[32m{{}}x\x1B[39m");
+}
+
+
 #[cfg(feature = "const")]
 #[test]
 fn test_sgr_const() {
@@ -54,20 +93,14 @@ fn test_sgr_const() {
         text,
         "\x1B[3mitalic \x1B[31mred \x1B[32mgreen\x1B[39m \x1B[34mblue\x1B[m",
     );
-}
 
+    const URL: &str = "https://example.com";
+    const LINK: &str = hyperlink!(URL; # "link");
+    assert_eq!(LINK, "\x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\");
 
-// #[cfg(not(feature = "const"))]
-// #[test]
-// #[should_panic] // TODO: Any way to specify that it should not *compile*?
-// fn test_sgr_const_fail() {
-//     let _err: &str = sgr_italic!(#*,
-//         "italic {r} {} {b}",
-//         green!("green"),
-//         b = blue!(! "blue"),
-//         r = red!(! "red"),
-//     );
-// }
+    const PADDED: &str = red!(# "{:>4}|", green!("ab"));
+    assert_eq!(PADDED, "\x1B[31m  \x1B[32mab\x1B[39m\x1B[31m|\x1B[39m");
+}
 
 
 #[test]
@@ -107,6 +140,107 @@ fn test_sgr_string() {
 }
 
 
+#[test]
+fn test_sgr_write() {
+    use std::{fmt::{self, Display, Write as _}, io::Write as _};
+
+    struct Warning(&'static str);
+
+    impl Display for Warning {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            yellow!(> f; "warning: {}", self.0)
+        }
+    }
+
+    assert_eq!(
+        Warning("low fuel").to_string(),
+        "\x1B[33mwarning: low fuel\x1B[39m",
+    );
+
+    let mut text = String::new();
+    green!(> text; "green {}", 1).unwrap();
+    green!(>text;* "green {}", 2).unwrap();
+    green!(> &mut text; ! "green {}", 3).unwrap();
+    sgr_markup!(> text; "<b>{}</b>", 4).unwrap();
+    assert_eq!(
+        text,
+        concat!(
+            "\x1B[32mgreen 1\x1B[39m",
+            "\x1B[32mgreen 2\x1B[m",
+            "\x1B[32mgreen 3",
+            "\x1B[1m4\x1B[22m",
+        ),
+    );
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let result: std::io::Result<()> = sgr_bold!(> bytes; "{:>3}", red!("x"));
+    result.unwrap();
+    cursor_up!(> bytes; 2).unwrap();
+    assert_eq!(bytes, b"\x1B[1m  \x1B[31mx\x1B[39m\x1B[22m\x1B[2A");
+}
+
+
+#[test]
+fn test_sgr_print() {
+    //  The printed text cannot be read back, but each form must expand to a
+    //      print macro, in every reversion mode.
+    let () = red!(>> "error: {}", 1);
+    let () = red!(>>! "error: {}", 2);
+    let () = red!(>>!! "error: {}", 2);
+    let () = red!(>>!? "error: {}", 2);
+    let () = red!(1>>! "error: {}", 2);
+    let () = red!(1>* "error: {}\n", 3);
+    let () = red!(1>> "error: {}", 4);
+    let () = red!(2> "error: {}\n", 5);
+    let () = red!(2>>? "error: {}", green!("6"));
+    let () = sgr_markup!(2>>? "<b>error:</b> {}", 7);
+    let () = hyperlink!("https://a.b"; 2>>? "{:>4}", red!("8"));
+}
+
+
+#[test]
+fn test_sgr_lazy() {
+    use std::fmt::{Debug, Display};
+
+    fn label(name: String, count: usize) -> impl Display + Debug {
+        sgr_bold!(& move "{name}: {}", green!(& move "{}", count))
+    }
+
+    struct Entry<T: Display> {
+        label: T,
+    }
+
+    //  A value made from locals can be returned when they are moved into it.
+    fn total(items: &[usize]) -> impl Display {
+        let sum: usize = items.iter().sum();
+        red!(& move "{} items", sum)
+    }
+
+    assert_eq!(total(&[1, 2, 3]).to_string(), "\x1B[31m6 items\x1B[39m");
+
+    let entry = Entry { label: label(String::from("apples"), 3) };
+    let text = "\x1B[1mapples: \x1B[32m3\x1B[39m\x1B[22m";
+    assert_eq!(entry.label.to_string(), text);
+    assert_eq!(format!("{:?}", entry.label), format!("{text:?}"));
+
+    //  Debug output is escaped as it is written, as for a string.
+    let lazy = red!(& "'{}\"\\", "e\u{301}\n");
+    assert_eq!(format!("{lazy:?}"), format!("{:?}", lazy.to_string()));
+
+    //  Arguments are borrowed, and evaluated each time the value is displayed.
+    let count = std::cell::Cell::new(0);
+    let formatter = "f";
+    let lazy = red!(&* "{formatter}{}", { count.set(count.get() + 1); 1 });
+    assert_eq!(count.get(), 0);
+    assert_eq!(lazy.to_string(), "\x1B[31mf1\x1B[m");
+    assert_eq!(format!("{lazy}{lazy}"), "\x1B[31mf1\x1B[m\x1B[31mf1\x1B[m");
+    assert_eq!(count.get(), 3);
+
+    let lazy = green!(&! "{:>3}", red!("x"));
+    assert_eq!(lazy.to_string(), "\x1B[32m  \x1B[31mx\x1B[39m\x1B[32m");
+}
+
+
 #[test]
 fn test_sgr_rgb() {
     //  Literal `0xRRGGBB` hex format.
@@ -121,12 +255,6 @@ fn test_sgr_rgb() {
         "\x1B[38;2;255;255;255mRGB text\x1B[39m",
     );
 
-    // //  Above maximum value; Should not compile.
-    // assert_eq!(
-    //     color_rgb!(0x1000000; "RGB text"),
-    //     "\x1B[38;2;255;255;255mRGB text\x1B[39m",
-    // );
-
     assert_eq!(
         color_rgb!(0xFF55FF; "RGB text"),
         "\x1B[38;2;255;85;255mRGB text\x1B[39m",
@@ -144,6 +272,68 @@ fn test_sgr_rgb() {
         "\x1B[38;2;255;85;255mRGB text\x1B[39m",
     );
 
+    //  CSS named colors.
+    assert_eq!(
+        color_rgb!("rebeccapurple"; "RGB text"),
+        "\x1B[38;2;102;51;153mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb_bg!(steelblue; "RGB text"),
+        "\x1B[48;2;70;130;180mRGB text\x1B[49m",
+    );
+    assert_eq!(
+        color_rgb!("SteelBlue"; "RGB text"),
+        color_rgb!(0x4682B4; "RGB text"),
+    );
+    assert_eq!(
+        color_rgb!(grey; "RGB text"),
+        color_rgb!(gray; "RGB text"),
+    );
+
+    //  CSS color functions.
+    assert_eq!(
+        color_rgb!(rgb(255 127 0); "RGB text"),
+        "\x1B[38;2;255;127;0mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb!(rgb(100%, 50%, 0%); "RGB text"),
+        "\x1B[38;2;255;128;0mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb!(hsl(210, 40%, 50%); "RGB text"),
+        "\x1B[38;2;77;128;179mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb!(hsl(120 100% 25%); "RGB text"),
+        color_rgb!(green; "RGB text"),
+    );
+    assert_eq!(
+        color_rgb!(hsl(-120, 100, 50); "RGB text"),
+        color_rgb!(hsl(0.6666666667turn, 100, 50); "RGB text"),
+    );
+    assert_eq!(
+        color_rgb_bg!(hsv(210deg 40% 50%); "RGB text"),
+        "\x1B[48;2;77;102;128mRGB text\x1B[49m",
+    );
+    assert_eq!(
+        color_rgb!(oklch(0.7 0.1 250); "RGB text"),
+        "\x1B[38;2;109;163;218mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb!("oklch(70% 25% 250)"; "RGB text"),
+        color_rgb!(oklch(0.7 0.1 250); "RGB text"),
+    );
+
+    //  Out-of-gamut OKLCH colors keep their lightness.
+    assert_eq!(
+        color_rgb!(oklch(1 0.4 30); "RGB text"),
+        color_rgb!(white; "RGB text"),
+    );
+    assert_eq!(
+        color_rgb!(oklch(0.7 100% 180); "RGB text"),
+        "\x1B[38;2;0;184;161mRGB text\x1B[39m",
+    );
+
     //  Tuple format (all integers).
     assert_eq!(
         color_rgb!((255, 127, 0); "RGB text"),
@@ -216,6 +406,58 @@ fn test_sgr_rgb() {
 }
 
 
+#[test]
+fn test_sgr_palette_256() {
+    assert_eq!(
+        color_rgb!(0xFF8700, color_256; "RGB text"),
+        "\x1B[38;5;208mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb_bg!(0x5F87AA, color_256; "RGB text"),
+        "\x1B[48;5;67mRGB text\x1B[49m",
+    );
+    assert_eq!(
+        uline_color_rgb!("#808080", color_256; @ "{}", "RGB text"),
+        "\x1B[58;5;244mRGB text\x1B[59m",
+    );
+
+    //  Only the invocation with the option is affected.
+    assert_eq!(
+        color_rgb!(0xFF8700; color_rgb!(0xFF8700, color_256; "RGB text")),
+        "\x1B[38;5;208mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb!(0xFF8700; "RGB text"),
+        "\x1B[38;2;255;135;0mRGB text\x1B[39m",
+    );
+}
+
+
+#[test]
+fn test_sgr_palette_16() {
+    assert_eq!(
+        color_rgb!(0xB22222, color_16; "RGB text"),
+        "\x1B[31mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb!(0xFF4500, color_16; "RGB text"),
+        "\x1B[91mRGB text\x1B[39m",
+    );
+    assert_eq!(
+        color_rgb_bg!(0xC0C0C0, color_16; "RGB text"),
+        "\x1B[47mRGB text\x1B[49m",
+    );
+    assert_eq!(
+        color_rgb_bg!(white, color_16; "RGB text"),
+        "\x1B[107mRGB text\x1B[49m",
+    );
+    assert_eq!(
+        sgr_gradient!(red -> blue, color_16; "ab"),
+        "\x1B[91ma\x1B[34mb\x1B[39m",
+    );
+}
+
+
 #[test]
 fn test_sgr_256() {
     assert_eq!(
@@ -238,3 +480,643 @@ fn test_sgr_256() {
         "\x1B[48;5;128mIndexed-color text\x1B[m",
     );
 }
+
+
+#[test]
+fn test_sgr_uline() {
+    assert_eq!(sgr_uline_double!("text"), "\x1B[4:2mtext\x1B[24m");
+    assert_eq!(sgr_uline_curly!("text"), "\x1B[4:3mtext\x1B[24m");
+    assert_eq!(sgr_uline_dotted!("text"), "\x1B[4:4mtext\x1B[24m");
+    assert_eq!(sgr_uline_dashed!("text"), "\x1B[4:5mtext\x1B[24m");
+
+    assert_eq!(
+        uline_color_256!(160; "text"),
+        "\x1B[58;5;160mtext\x1B[59m",
+    );
+    assert_eq!(
+        uline_color_rgb!(hsl(0, 100%, 50%); @ "{}", sgr_uline_curly!("text")),
+        "\x1B[58;2;255;0;0m\x1B[4:3mtext\x1B[24m\x1B[59m",
+    );
+
+    //  Underline styles share a closing parameter, as do underline colors.
+    assert_eq!(
+        sgr_uline!(concat!("a ", sgr_uline_curly!("b"), " c")),
+        "\x1B[4ma \x1B[4:3mb\x1B[4m c\x1B[24m",
+    );
+    assert_eq!(
+        uline_color_256!(1; @ "a {} c", uline_color_256!(2; "b")),
+        "\x1B[58;5;1ma \x1B[58;5;2mb\x1B[59m\x1B[58;5;1m c\x1B[59m",
+    );
+
+    assert_eq!(
+        sgr!(uline_curly, ul = red, uline_color_256 = 4; "text"),
+        "\x1B[4:3;58;2;255;0;0;58;5;4mtext\x1B[24;59m",
+    );
+    assert_eq!(
+        sgr_markup!(
+            "<uline_dashed><uline_rgb=#00F>text</uline_rgb></uline_dashed>"
+        ),
+        "\x1B[4:5;58;2;0;0;255mtext\x1B[59;24m",
+    );
+    assert_eq!(
+        sgr_uline_curly!(? "text"),
+        ("\x1B[4:3mtext\x1B[24m", "text"),
+    );
+}
+
+
+#[test]
+fn test_sgr_hyperlink() {
+    assert_eq!(
+        hyperlink!("https://example.com/{x}"; "link"),
+        "\x1B]8;;https://example.com/{x}\x1B\\link\x1B]8;;\x1B\\",
+    );
+    assert_eq!(
+        hyperlink!("https://example.com/{x}", id = "a"; @ "{}", 1),
+        "\x1B]8;id=a;https://example.com/{x}\x1B\\1\x1B]8;;\x1B\\",
+    );
+    assert_eq!(
+        hyperlink!("file:///tmp"; ! "link"),
+        "\x1B]8;;file:///tmp\x1B\\link",
+    );
+    assert_eq!(
+        hyperlink!("file:///tmp"; * "link"),
+        "\x1B]8;;file:///tmp\x1B\\link\x1B]8;;\x1B\\\x1B[m",
+    );
+
+    //  Parameters may be known only at runtime.
+    let url = format!("https://example.com/{}", 42);
+    let id = 7;
+    assert_eq!(
+        hyperlink!(url, id = id; @ "{}", sgr_bold!("link")),
+        concat!(
+            "\x1B]8;id=7;https://example.com/42\x1B\\",
+            "\x1B[1mlink\x1B[22m\x1B]8;;\x1B\\",
+        ),
+    );
+    assert_eq!(
+        format!("{}", hyperlink!(&url; % "link")),
+        "\x1B]8;;https://example.com/42\x1B\\link\x1B]8;;\x1B\\",
+    );
+
+    //  Nested colors are restored after a link that reverts them.
+    assert_eq!(
+        red!(@ "{}!", hyperlink!("https://a.b"; * "link")),
+        concat!(
+            "\x1B[31m\x1B]8;;https://a.b\x1B\\link\x1B]8;;\x1B\\\x1B[m",
+            "\x1B[31m!\x1B[39m",
+        ),
+    );
+}
+
+
+#[test]
+fn test_sgr_composite() {
+    assert_eq!(
+        sgr!(bold, red, bg = 0x223344; "text"),
+        "\x1B[1;31;48;2;34;51;68mtext\x1B[22;39;49m",
+    );
+
+    //  Full macro names are accepted as well as short style names.
+    assert_eq!(
+        sgr!(sgr_uline, green_bright_bg; "text"),
+        sgr!(uline, green_bright_bg; "text"),
+    );
+
+    //  Indexed and RGB colors.
+    assert_eq!(
+        sgr!(color_256 = 173, color_256_bg = 64; "text"),
+        "\x1B[38;5;173;48;5;64mtext\x1B[39;49m",
+    );
+    assert_eq!(
+        sgr!(color_rgb = "#ABC", color_rgb_bg = (255, 127, 0); "text"),
+        "\x1B[38;2;170;187;204;48;2;255;127;0mtext\x1B[39;49m",
+    );
+
+    //  Shared closing parameters are not repeated.
+    assert_eq!(
+        sgr!(bold, faint, fg = 0x420311; "text"),
+        "\x1B[1;2;38;2;66;3;17mtext\x1B[22;39m",
+    );
+
+    //  Output and revert modes.
+    let args: Arguments = sgr!(italic, cyan; % "{} text", "cyan");
+    assert_eq!(args.to_string(), "\x1B[3;36mcyan text\x1B[23;39m");
+
+    let text: String = sgr!(italic, cyan; @* "{} text", "cyan");
+    assert_eq!(text, "\x1B[3;36mcyan text\x1B[m");
+
+    let text: &str = sgr!(strike, yellow_bg; ! "text");
+    assert_eq!(text, "\x1B[9;43mtext");
+}
+
+
+sgr_macros::define_style! {
+    warning = bold + yellow + bg(0x202020);
+    highlight = sgr_invert + color_256(173) + uline_color_rgb("#FF0000");
+}
+
+
+#[test]
+fn test_define_style() {
+    let text: &str = warning!("text");
+    assert_eq!(text, "\x1B[1;33;48;2;32;32;32mtext\x1B[22;39;49m");
+    assert_eq!(text, sgr!(bold, yellow, bg = 0x202020; "text"));
+
+    assert_eq!(warning!(! "text"), "\x1B[1;33;48;2;32;32;32mtext");
+    assert_eq!(warning!(* "text"), "\x1B[1;33;48;2;32;32;32mtext\x1B[m");
+    assert_eq!(
+        warning!(% "{} warnings", 3).to_string(),
+        "\x1B[1;33;48;2;32;32;32m3 warnings\x1B[22;39;49m",
+    );
+
+    let text: String = highlight!(@* "{}", 5);
+    assert_eq!(text, "\x1B[7;38;5;173;58;2;255;0;0m5\x1B[m");
+
+    #[cfg(feature = "const")] {
+        const TEXT: &str = highlight!(#! "{}", 5_u8);
+        assert_eq!(TEXT, "\x1B[7;38;5;173;58;2;255;0;0m5");
+    }
+
+    //  Nested invocations are detected within the new macros.
+    assert_eq!(
+        warning!(concat!("a ", red!("b"), " c")),
+        "\x1B[1;33;48;2;32;32;32ma \x1B[31mb\x1B[33m c\x1B[22;39;49m",
+    );
+
+    //  The new macros are not detected within another macro, so an enclosing
+    //      style is not restored after them.
+    assert_eq!(
+        red!(concat!("a ", warning!("b"), " c")),
+        "\x1B[31ma \x1B[1;33;48;2;32;32;32mb\x1B[22;39;49m c\x1B[39m",
+    );
+}
+
+
+#[test]
+fn test_sgr_nested() {
+    //  Nested styles that share a revert sequence re-apply the outer style.
+    assert_eq!(
+        red!(concat!("a ", green!("b"), " c")),
+        "\x1B[31ma \x1B[32mb\x1B[31m c\x1B[39m",
+    );
+    assert_eq!(
+        sgr_bold!("a ", sgr_macros::sgr_faint!("b"), " c"),
+        "\x1B[1ma \x1B[2mb\x1B[22;1m c\x1B[22m",
+    );
+    assert_eq!(
+        red_bg!("a ", color_256_bg!(64; "b"), " c"),
+        "\x1B[41ma \x1B[48;5;64mb\x1B[41m c\x1B[49m",
+    );
+
+    //  Bright colors are closed like any other color of their layer.
+    assert_eq!(
+        red!("a ", green_bright!("b"), " c"),
+        "\x1B[31ma \x1B[92mb\x1B[31m c\x1B[39m",
+    );
+    assert_eq!(
+        blue_bright_bg!("a ", red_bg!("b"), " c"),
+        "\x1B[104ma \x1B[41mb\x1B[104m c\x1B[49m",
+    );
+
+    //  Styles that do not conflict are left alone.
+    assert_eq!(
+        red!("a ", sgr_bold!("b"), " c"),
+        "\x1B[31ma \x1B[1mb\x1B[22m c\x1B[39m",
+    );
+
+    //  A nested style that does not revert is deliberately left open.
+    assert_eq!(
+        red!("a ", green!(! "b"), " c"),
+        "\x1B[31ma \x1B[32mb c\x1B[39m",
+    );
+
+    //  A nested style that reverts everything always conflicts.
+    assert_eq!(
+        sgr_italic!("a ", green!(* "b"), " c"),
+        "\x1B[3ma \x1B[32mb\x1B[0;3m c\x1B[23m",
+    );
+
+    //  Conflicts are found at any depth.
+    assert_eq!(
+        red!("a ", sgr_bold!("b ", green!("c")), " d"),
+        "\x1B[31ma \x1B[1mb \x1B[32mc\x1B[22;31m d\x1B[39m",
+    );
+
+    //  Composite styles conflict with any of their attributes.
+    assert_eq!(
+        sgr!(bold, blue; "a ", green!("b"), " c"),
+        "\x1B[1;34ma \x1B[32mb\x1B[34m c\x1B[22;39m",
+    );
+}
+
+
+#[test]
+fn test_sgr_nested_template() {
+    let text: String = red!(@ "a {} c", green!("b"));
+    assert_eq!(text, "\x1B[31ma \x1B[32mb\x1B[39m\x1B[31m c\x1B[39m");
+
+    //  Named and explicitly-indexed arguments are followed as well.
+    let text: String = red!(@
+        "{0} {g} {1} {0}",
+        "x",
+        sgr_bold!("y"),
+        g = green!("g"),
+    );
+    assert_eq!(
+        text,
+        "\x1B[31mx \x1B[32mg\x1B[39m\x1B[31m \x1B[1my\x1B[22m x\x1B[39m",
+    );
+
+    let args: Arguments = blue!(% "a {} c", color_rgb!(0xFF7F00; % "{}", 'b'));
+    assert_eq!(
+        args.to_string(),
+        "\x1B[34ma \x1B[38;2;255;127;0mb\x1B[39m\x1B[34m c\x1B[39m",
+    );
+}
+
+
+#[test]
+fn test_control() {
+    const UP: &str = cursor_up!(3);
+    assert_eq!(UP, "\x1B[3A");
+
+    assert_eq!(cursor_up!(), "\x1B[A");
+    assert_eq!(cursor_down!(1), "\x1B[1B");
+    assert_eq!(cursor_forward!(12), "\x1B[12C");
+    assert_eq!(cursor_back!(65535), "\x1B[65535D");
+    assert_eq!(cursor_position!(), "\x1B[H");
+    assert_eq!(cursor_position!(5), "\x1B[5H");
+    assert_eq!(cursor_position!(5, 10), "\x1B[5;10H");
+    assert_eq!(cursor_save!(), "\x1B[s");
+    assert_eq!(cursor_restore!(), "\x1B[u");
+    assert_eq!(cursor_show!(), "\x1B[?25h");
+    assert_eq!(cursor_hide!(), "\x1B[?25l");
+    assert_eq!(erase_line!(), "\x1B[K");
+    assert_eq!(erase_line!(2), "\x1B[2K");
+    assert_eq!(erase_display!(0), "\x1B[0J");
+    assert_eq!(erase_display!(3), "\x1B[3J");
+    assert_eq!(scroll_region!(), "\x1B[r");
+    assert_eq!(scroll_region!(2, 24), "\x1B[2;24r");
+
+    //  Parameters known only at runtime require an output mode.
+    let n = 4;
+    let args: Arguments = cursor_up!(% "{}", n);
+    assert_eq!(args.to_string(), "\x1B[4A");
+    assert_eq!(cursor_up!(@ n), "\x1B[4A");
+    assert_eq!(cursor_position!(@ n, 2), "\x1B[4;2H");
+    assert_eq!(cursor_position!(@ 2, n + 1), "\x1B[2;5H");
+    assert_eq!(cursor_position!(@ "{};{}", n, n), "\x1B[4;4H");
+    assert_eq!(cursor_hide!(@), "\x1B[?25l");
+    assert_eq!(scroll_region!(% 1, n).to_string(), "\x1B[1;4r");
+
+    //  A template may write the parameters in another order.
+    assert_eq!(scroll_region!(@ "{1};{0}", 24, 2), "\x1B[2;24r");
+
+    //  A progress bar may redraw its line.
+    assert_eq!(
+        concat!(erase_line!(2), "\r", green!("50%")),
+        "\x1B[2K\r\x1B[32m50%\x1B[39m",
+    );
+    assert_eq!(sgr_strip!(concat!(cursor_up!(2), erase_line!(2), "x")), "x");
+}
+
+
+#[cfg(feature = "const")]
+#[test]
+fn test_control_const() {
+    const ROW: u16 = 7;
+    const MOVE: &str = cursor_position!(# ROW, 1);
+    assert_eq!(MOVE, "\x1B[7;1H");
+}
+
+
+#[test]
+fn test_sgr_markup() {
+    assert_eq!(
+        sgr_markup!("<b><red>error</red></b>: <u>path</u>"),
+        "\x1B[1;31merror\x1B[39;22m: \x1B[4mpath\x1B[24m",
+    );
+
+    //  Full macro names, RGB colors, and indexed colors.
+    assert_eq!(
+        sgr_markup!("<sgr_italic><cyan_bg>a</cyan_bg></sgr_italic>"),
+        "\x1B[3;46ma\x1B[49;23m",
+    );
+    assert_eq!(
+        sgr_markup!("<rgb=#AABBCC>a</rgb><rgb_bg=0x420311>b</rgb_bg>"),
+        "\x1B[38;2;170;187;204ma\x1B[39;48;2;66;3;17mb\x1B[49m",
+    );
+    assert_eq!(
+        sgr_markup!("<256=173>a</256> <256_bg=64>b</256_bg>"),
+        "\x1B[38;5;173ma\x1B[39m \x1B[48;5;64mb\x1B[49m",
+    );
+
+    //  Escaped angle brackets.
+    assert_eq!(sgr_markup!("a << b > c"), "a < b > c");
+
+    //  Enclosing tags are applied again when they share a revert sequence.
+    assert_eq!(
+        sgr_markup!("<bold>a <faint>b</faint> c</bold>"),
+        "\x1B[1ma \x1B[2mb\x1B[22;1m c\x1B[22m",
+    );
+
+    //  Revert modes.
+    assert_eq!(
+        sgr_markup!(* "<b>a</b> <red>b"),
+        "\x1B[1ma\x1B[22m \x1B[31mb\x1B[m",
+    );
+    assert_eq!(
+        sgr_markup!(! "<b>a</b> <red>b"),
+        "\x1B[1ma\x1B[22m \x1B[31mb",
+    );
+}
+
+
+#[test]
+fn test_sgr_markup_template() {
+    let msg = "not found";
+    let path = "/etc/hosts";
+
+    let text: String = sgr_markup!(@
+        "<b><red>error</red></b>: {msg} <u>{path}</u>",
+        msg = msg,
+        path = path,
+    );
+    assert_eq!(
+        text,
+        "\x1B[1;31merror\x1B[39;22m: not found \x1B[4m/etc/hosts\x1B[24m",
+    );
+
+    //  Tags are not recognized within placeholders.
+    let args: Arguments = sgr_markup!(% "<u>{:<5}</u>|", 'x');
+    assert_eq!(args.to_string(), "\x1B[4mx    \x1B[24m|");
+
+    //  Nested invocations in arguments re-apply enclosing tags.
+    let text: String = sgr_markup!(@ "<red>a {} c</red>", green!("b"));
+    assert_eq!(text, "\x1B[31ma \x1B[32mb\x1B[39m\x1B[31m c\x1B[39m");
+
+    //  Literal Mode fills the template at compile-time.
+    const TEXT: &str = sgr_markup!(
+        "<b><red>error</red></b>: {msg} <u>{path:>4}</u>",
+        msg = "not found",
+        path = "/a",
+    );
+    assert_eq!(
+        TEXT,
+        "\x1B[1;31merror\x1B[39;22m: not found \x1B[4m  /a\x1B[24m",
+    );
+    assert_eq!(
+        sgr_markup!("<red>a {} c</red>", green!("b")),
+        "\x1B[31ma \x1B[32mb\x1B[31m c\x1B[39m",
+    );
+    assert_eq!(sgr_markup!("<b>{{{}}}</b>", "x"), "\x1B[1m{x}\x1B[22m");
+}
+
+
+#[test]
+fn test_sgr_strip() {
+    const TEXT: &str = sgr_strip!(red!(concat!("a ", green!("b"), " c")));
+    assert_eq!(TEXT, "a b c");
+
+    assert_eq!(sgr_strip!("plain"), "plain");
+    assert_eq!(sgr_strip!(sgr!(bold, bg = 0x223344; "x")), "x");
+    assert_eq!(sgr_strip!(color_256!(173; * "x")), "x");
+    assert_eq!(sgr_strip!(uline_color_rgb!(red; sgr_uline_curly!("x"))), "x");
+    assert_eq!(sgr_strip!(sgr_markup!("<b>a</b> <<3")), "a <3");
+    assert_eq!(sgr_strip!(concat!("a", sgr_reset!(), 1, 'c')), "a1c");
+    assert_eq!(
+        sgr_strip!(hyperlink!("https://docs.rs", id = "d"; blue!("Docs"))),
+        "Docs",
+    );
+
+    //  Other CSI sequences, and commands ended by BEL, are also removed.
+    assert_eq!(sgr_strip!("\x1B[2K\x1B[1;5Ha\x1B]0;title\x07b"), "ab");
+    assert_eq!(sgr_strip!(sgr_strip!(red!("x"))), "x");
+}
+
+
+#[test]
+fn test_sgr_width() {
+    const WIDTH: usize = sgr_width!(green!("ok"));
+    assert_eq!(WIDTH, 2);
+
+    assert_eq!(sgr_width!(""), 0);
+    assert_eq!(sgr_width!(concat!(red!("ab"), " ", sgr_bold!("cd"))), 5);
+    assert_eq!(sgr_width!(hyperlink!("https://docs.rs"; "Docs")), 4);
+
+    //  East Asian Wide characters take two columns.
+    assert_eq!(sgr_width!(red!("日本語")), 6);
+    assert_eq!(sgr_width!("ｆｕｌｌ"), 8);
+
+    //  Combining marks and zero-width characters take none.
+    assert_eq!(sgr_width!("e\u{301}"), 1);
+    assert_eq!(sgr_width!("a\u{200B}b"), 2);
+
+    //  Emoji take two columns, including a sequence joined by ZWJ.
+    assert_eq!(sgr_width!("🦀"), 2);
+    assert_eq!(sgr_width!(green!("👨\u{200D}👩\u{200D}👧")), 2);
+}
+
+
+#[test]
+fn test_sgr_html() {
+    const HTML: &str = sgr_html!(red!(concat!("a ", green!("b"), " c")));
+    assert_eq!(
+        HTML,
+        concat!(
+            "<span style=\"color:#cd0000\">a </span>",
+            "<span style=\"color:#00cd00\">b</span>",
+            "<span style=\"color:#cd0000\"> c</span>",
+        ),
+    );
+
+    assert_eq!(sgr_html!("a < b & c"), "a &lt; b &amp; c");
+    assert_eq!(
+        sgr_html!(classes; sgr!(bold, uline_curly; "x")),
+        "<span class=\"sgr-bold sgr-uline-curly\">x</span>",
+    );
+    assert_eq!(
+        sgr_html!(hyperlink!("https://docs.rs"; sgr_italic!("Docs"))),
+        concat!(
+            "<a href=\"https://docs.rs\">",
+            "<span style=\"font-style:italic\">Docs</span></a>",
+        ),
+    );
+}
+
+
+#[test]
+fn test_sgr_pad() {
+    //  Padding counts the columns of visible text, not control sequences.
+    assert_eq!(
+        sgr_bold!(@ "[{:<4}]", red!("ab")),
+        "\x1B[1m[\x1B[31mab\x1B[39m  ]\x1B[22m",
+    );
+    assert_eq!(
+        sgr_bold!(@ "[{:>4}]", red!("ab")),
+        "\x1B[1m[  \x1B[31mab\x1B[39m]\x1B[22m",
+    );
+    assert_eq!(
+        format!("{}", sgr_bold!(% "[{:-^7}]", red!("ab"))),
+        "\x1B[1m[--\x1B[31mab\x1B[39m---]\x1B[22m",
+    );
+
+    //  Wide characters take two columns, and combining marks take none.
+    assert_eq!(
+        red!(@ "{:6}|{:>3}|", "日本", "e\u{301}"),
+        "\x1B[31m日本  |  e\u{301}|\x1B[39m",
+    );
+    assert_eq!(
+        red!(@ "{x:.<5}|{0:*>4}", green!("ｆｕ"), x = "🦀"),
+        "\x1B[31m🦀...|\x1B[32mｆｕ\x1B[39m\x1B[31m\x1B[39m",
+    );
+
+    //  Text that does not fit is not truncated.
+    assert_eq!(red!(@ "{:2}|", blue!("abc")), red!(@ "{}|", blue!("abc")));
+
+    //  An argument used by more than one placeholder is padded separately.
+    assert_eq!(
+        sgr_uline!(@ "{0:>3}{0}", green!("日")),
+        concat!(
+            "\x1B[4m \x1B[32m日\x1B[39m",
+            "\x1B[32m日\x1B[39m\x1B[24m",
+        ),
+    );
+    assert_eq!(
+        sgr_bold!(@ "{:>4}|{}|{0}", red!("a"), "b"),
+        "\x1B[1m   \x1B[31ma\x1B[39m|b|\x1B[31ma\x1B[39m\x1B[22m",
+    );
+    assert_eq!(
+        red!("{:>4}|{}|{0}", "a", "b"),
+        "\x1B[31m   a|b|a\x1B[39m",
+    );
+    assert_eq!(
+        red!(@ "{0:>3}{0:<2}|", green!("x")),
+        concat!(
+            "\x1B[31m  \x1B[32mx\x1B[39m\x1B[31m",
+            "\x1B[32mx\x1B[39m \x1B[31m|\x1B[39m",
+        ),
+    );
+    assert_eq!(red!("{0:>3}{0:>3}", "x"), "\x1B[31m  x  x\x1B[39m");
+
+    //  In Literal Mode, any literal may be padded.
+    assert_eq!(
+        red!("[{:>4}|{:<3}|{:^5}]", 7, 'c', true),
+        "\x1B[31m[   7|c  |true ]\x1B[39m",
+    );
+
+    //  Text that is only known at runtime is measured at runtime.
+    let n = 12;
+    assert_eq!(
+        red!(@ "[{:>4}]", green!(% "{}", n)),
+        "\x1B[31m[  \x1B[32m12\x1B[39m\x1B[31m]\x1B[39m",
+    );
+    assert_eq!(
+        sgr_markup!(@ "[{:^6}]", blue!(@ "日{}", n)),
+        "[ \x1B[34m日12\x1B[39m ]",
+    );
+    assert_eq!(
+        hyperlink!("https://a.b"; @ "{:<5}|", red!(% "{}", n)),
+        "\x1B]8;;https://a.b\x1B\\\x1B[31m12\x1B[39m   |\x1B]8;;\x1B\\",
+    );
+
+    //  Other arguments keep the padding of the standard macros.
+    assert_eq!(red!(@ "{:>4}|{:04}", n, 7), "\x1B[31m  12|0007\x1B[39m");
+    assert_eq!(red!(@ "{:>4}", String::from("ab")), "\x1B[31m  ab\x1B[39m");
+}
+
+
+#[test]
+fn test_sgr_gradient() {
+    //  By default, colors are mixed in OKLab, which puts mid-gray halfway
+    //      between black and white.
+    const GRAY: &str = sgr_gradient!(0x000000 -> 0xFFFFFF; "abc");
+    assert_eq!(
+        GRAY,
+        concat!(
+            "\x1B[38;2;0;0;0ma",
+            "\x1B[38;2;99;99;99mb",
+            "\x1B[38;2;255;255;255mc\x1B[39m",
+        ),
+    );
+
+    //  Any number of stops may be given, and are evenly spaced.
+    assert_eq!(
+        sgr_gradient!(red -> lime -> blue, linear; "abcde"),
+        concat!(
+            "\x1B[38;2;255;0;0ma",
+            "\x1B[38;2;188;188;0mb",
+            "\x1B[38;2;0;255;0mc",
+            "\x1B[38;2;0;188;188md",
+            "\x1B[38;2;0;0;255me\x1B[39m",
+        ),
+    );
+    assert_eq!(
+        sgr_gradient!(red -> blue; "x"),
+        "\x1B[38;2;255;0;0mx\x1B[39m",
+    );
+
+    //  Each grapheme cluster has one color. Whitespace is only colored in the
+    //      background.
+    assert_eq!(
+        sgr_gradient!(red -> blue; "e\u{301}👨\u{200D}👩\u{200D}👧 x"),
+        concat!(
+            "\x1B[38;2;255;0;0me\u{301}",
+            "\x1B[38;2;179;79;128m👨\u{200D}👩\u{200D}👧 ",
+            "\x1B[38;2;0;0;255mx\x1B[39m",
+        ),
+    );
+    assert_eq!(
+        sgr_gradient!(red -> blue, bg; "a b"),
+        concat!(
+            "\x1B[48;2;255;0;0ma",
+            "\x1B[48;2;140;83;162m ",
+            "\x1B[48;2;0;0;255mb\x1B[49m",
+        ),
+    );
+
+    //  Text with nothing to color is not closed.
+    assert_eq!(sgr_gradient!(red -> blue; ""), "");
+    assert_eq!(sgr_gradient!(red -> blue; * ""), "");
+    assert_eq!(sgr_gradient!(red -> blue; " \t "), " \t ");
+    assert_eq!(
+        red!(concat!("a", sgr_gradient!(lime -> blue; " "), "b")),
+        "\x1B[31ma b\x1B[39m",
+    );
+    assert_eq!(
+        sgr_gradient!(red -> blue, bg; " "),
+        "\x1B[48;2;255;0;0m \x1B[49m",
+    );
+
+    //  Clusters with the same indexed color share a sequence.
+    assert_eq!(
+        sgr_gradient!(0x101010 -> 0x121212, color_256; "abcd"),
+        "\x1B[38;5;233mabcd\x1B[39m",
+    );
+
+    //  Reversion and gating work as in other macros.
+    assert_eq!(
+        sgr_gradient!(red -> blue; * "ab"),
+        "\x1B[38;2;255;0;0ma\x1B[38;2;0;0;255mb\x1B[m",
+    );
+    assert_eq!(
+        sgr_gradient!(red -> blue; ! "ab"),
+        "\x1B[38;2;255;0;0ma\x1B[38;2;0;0;255mb",
+    );
+    assert_eq!(
+        sgr_gradient!(red -> blue; ? "ab"),
+        ("\x1B[38;2;255;0;0ma\x1B[38;2;0;0;255mb\x1B[39m", "ab"),
+    );
+
+    //  A nested gradient reverts the outer color, which is then restored.
+    assert_eq!(
+        red!(concat!("a", sgr_gradient!(lime -> blue; "bc"), "d")),
+        concat!(
+            "\x1B[31ma\x1B[38;2;0;255;0mb",
+            "\x1B[38;2;0;0;255mc\x1B[31md\x1B[39m",
+        ),
+    );
+    assert_eq!(sgr_strip!(sgr_gradient!(red -> blue; "日本")), "日本");
+    assert_eq!(sgr_width!(sgr_gradient!(red -> blue; "日本")), 4);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "theme")]
+
+sgr_macros::sgr_theme!("tests/theme.toml");
+
+
+#[test]
+fn test_theme() {
+    assert_eq!(theme_error!("failed"), "\x1B[1;31mfailed\x1B[22;39m");
+    assert_eq!(theme_path!("/etc"), "\x1B[4m/etc\x1B[24m");
+    assert_eq!(
+        theme_warning!("careful"),
+        "\x1B[3;38;2;255;170;0;58;2;255;0;0mcareful\x1B[23;39;59m",
+    );
+    assert_eq!(
+        theme_badge!("new"),
+        "\x1B[7;38;5;173;48;5;16mnew\x1B[27;39;49m",
+    );
+
+    //  Every sigil is forwarded.
+    let (dir, name) = ("/etc", "hosts");
+    assert_eq!(
+        theme_path!(@ "{}/{}", dir, name),
+        format!("\x1B[4m{dir}/{name}\x1B[24m"),
+    );
+    assert_eq!(
+        theme_error!(%* "{} errors", 3).to_string(),
+        "\x1B[1;31m3 errors\x1B[m",
+    );
+    assert_eq!(theme_error!(! "failed"), "\x1B[1;31mfailed");
+    assert_eq!(
+        theme_path!(concat!("a", sgr_macros::red!("b"))),
+        "\x1B[4ma\x1B[31mb\x1B[39;24m",
+    );
+}
@@ -0,0 +1,10 @@
+use sgr_macros::*;
+
+fn main() {
+    let _err: &str = sgr_italic!(#*,
+        "italic {r} {} {b}",
+        green!("green"),
+        b = blue!(! "blue"),
+        r = red!(! "red"),
+    );
+}
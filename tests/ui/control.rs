@@ -0,0 +1,20 @@
+use sgr_macros::*;
+
+fn main() {
+    let n = 2;
+
+    let _ = cursor_up!(0);
+    let _ = cursor_up!(65536);
+    let _ = cursor_up!(-1);
+    let _ = cursor_up!(n);
+    let _ = cursor_up!(1, 2);
+    let _ = cursor_save!(1);
+    let _ = erase_line!(3);
+    let _ = erase_display!(% 4);
+    let _ = scroll_region!(10, 5);
+    let _ = scroll_region!(@ "{1};{0}", 5, 10);
+    let _ = scroll_region!(@ "10;{}", 5);
+    let _ = cursor_up!(? 1);
+    let _ = cursor_up!(* 1);
+    let _ = cursor_position!(@ "{}H{}", n, n);
+}
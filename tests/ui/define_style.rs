@@ -0,0 +1,7 @@
+sgr_macros::define_style!(a = bold + blod);
+sgr_macros::define_style!(b = bold + bg);
+sgr_macros::define_style!(c = sgr_italic + bold(1));
+sgr_macros::define_style!(d = fg(0x1000000));
+sgr_macros::define_style!(e = bold yellow);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use sgr_macros::*;
+
+fn main() {
+    let _ = sgr_gradient!(red; "text");
+    let _ = sgr_gradient!(red -> blu; "text");
+    let _ = sgr_gradient!(red -> blue, hsv; "text");
+    let _ = sgr_gradient!(red -> blue; @ "{}", 1);
+    let _ = sgr_gradient!(red -> blue; concat!("a", "b"));
+}
@@ -0,0 +1,9 @@
+fn main() {
+    let url = "https://example.com";
+
+    let _ = sgr_macros::hyperlink!("example.com"; "link");
+    let _ = sgr_macros::hyperlink!("https://example.com/a b"; "link");
+    let _ = sgr_macros::hyperlink!("https://example.com", id = "a;b"; "link");
+    let _ = sgr_macros::hyperlink!("https://example.com", name = "a"; "link");
+    let _ = sgr_macros::hyperlink!(url; "link");
+}
@@ -0,0 +1,11 @@
+use sgr_macros::*;
+
+fn main() {
+    let count = 3;
+    let _ = green!("{} items", count);
+    let _ = green!("{} items", "3", "4");
+    let _ = green!("{name} items", "3");
+    let _ = green!("{1} items", "3");
+    let _ = green!("{:?} items", "3");
+    let _ = sgr_markup!("<b>{}</b> items", count);
+}
@@ -0,0 +1,8 @@
+use sgr_macros::*;
+
+fn main() {
+    let mut text = String::new();
+    let _ = red!(> text "x");
+    let _ = red!(3> "x");
+    let _ = red!(0>> "x");
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let _ = sgr_macros::color_rgb!("#FFAABBCC"; "RGB text");
+    let _ = sgr_macros::color_rgb!(0x1000000; "RGB text");
+}
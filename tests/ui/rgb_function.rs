@@ -0,0 +1,6 @@
+fn main() {
+    let _ = sgr_macros::color_rgb!(hsl(210, 40%); "RGB text");
+    let _ = sgr_macros::color_rgb!(hsl(210%, 40%, 50%); "RGB text");
+    let _ = sgr_macros::color_rgb!(lab(50 20 20); "RGB text");
+    let _ = sgr_macros::color_rgb!("hsl(210 40% 50px)"; "RGB text");
+}
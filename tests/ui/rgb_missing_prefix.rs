@@ -0,0 +1,3 @@
+fn main() {
+    let _ = sgr_macros::color_rgb!("AABBCC"; "RGB text");
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = sgr_macros::color_rgb!(0xFF8700, color_8; "RGB text");
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let _ = sgr_macros::color_rgb!("rebecapurple"; "RGB text");
+    let _ = sgr_macros::color_rgb!(stealblue; "RGB text");
+    let _ = sgr_macros::color_rgb_bg!(notacolor; "RGB text");
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let _ = sgr_macros::color_rgb!("#ABCD"; "RGB text");
+    let _ = sgr_macros::color_rgb_bg!("0x"; "RGB text");
+}
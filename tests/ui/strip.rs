@@ -0,0 +1,12 @@
+use sgr_macros::*;
+
+const TEXT: &str = "text";
+
+fn main() {
+    let _ = sgr_strip!(TEXT);
+    let _ = sgr_strip!(green!(@ "text"));
+    let _ = sgr_width!(concat!("a", red!(? "b")));
+    let _ = sgr_width!(b"bytes");
+    let _ = sgr_html!(css; red!("text"));
+    let _ = sgr_html!(classes; TEXT);
+}